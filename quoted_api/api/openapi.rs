@@ -0,0 +1,8 @@
+use quoted_api::{handlers::openapi::GetOpenApi, runtime::vercel, setup::setup};
+use vercel_runtime::Error;
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    setup();
+    vercel::run::<GetOpenApi>().await
+}
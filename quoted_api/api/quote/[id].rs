@@ -0,0 +1,12 @@
+use quoted_api::{
+    handlers::{delete_quote::DeleteQuote, quote::GetQuote, update_quote::UpdateQuote},
+    runtime::vercel,
+    setup::setup,
+};
+use vercel_runtime::Error;
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    setup();
+    vercel::run::<(GetQuote, UpdateQuote, DeleteQuote)>().await
+}
@@ -0,0 +1,8 @@
+use quoted_api::{handlers::search_quotes::SearchQuotes, runtime::vercel, setup::setup};
+use vercel_runtime::Error;
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    setup();
+    vercel::run::<SearchQuotes>().await
+}
@@ -0,0 +1,8 @@
+use quoted_api::{handlers::stats::GetStats, runtime::vercel, setup::setup};
+use vercel_runtime::Error;
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    setup();
+    vercel::run::<GetStats>().await
+}
@@ -0,0 +1,237 @@
+//!
+//! Compares the time taken to pick a random quote by ordering every matching row
+//! by `random()` against `pick_random_quote_ids`, which picks a quote by its
+//! position among the matching quotes.
+//!
+//! Runs against the database configured in `quoted_api/.env`:
+//!
+//! ```sh
+//! cargo bench -p quoted_api --bench random_quote
+//! ```
+//!
+//! The difference only really shows with a large data set. Set `BENCH_EXTRA_QUOTES`
+//! to add that many copies of existing quotes before running. Everything happens
+//! in a transaction that's rolled back, so the database is left as it was.
+//! `BENCH_ITERATIONS` sets how many times each query is run (default `20`).
+//!
+
+use std::{
+    env,
+    future::Future,
+    time::{Duration, Instant},
+};
+
+use quoted_api::handlers::random_quote::pick_random_quote_ids;
+use quoted_api_models::quote::GetRandomQuoteRequestParams;
+use quoted_db::get_default_connection;
+use quoted_db_entity as entity;
+use rand::{rngs::StdRng, SeedableRng};
+use sea_orm::{
+    entity::*,
+    sea_query::{Func, SimpleExpr},
+    ConnectionTrait, DatabaseTransaction, EntityTrait, JoinType, Order, PaginatorTrait,
+    QueryFilter, QuerySelect, QueryTrait, Statement, TransactionTrait,
+};
+use vercel_runtime::Error;
+
+const DEFAULT_ITERATIONS: usize = 20;
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    let _ = dotenvy::dotenv();
+    let iterations = get_env_var::<usize>("BENCH_ITERATIONS")?
+        .unwrap_or(DEFAULT_ITERATIONS)
+        .max(1);
+    let extra_quotes = get_env_var::<i64>("BENCH_EXTRA_QUOTES")?.unwrap_or_default();
+
+    let db = get_default_connection().await?;
+    let txn = db.begin().await?;
+
+    if extra_quotes > 0 {
+        println!("Adding {extra_quotes} extra quotes");
+        add_extra_quotes(&txn, extra_quotes).await?;
+    }
+
+    let quote_count = entity::quote::Entity::find().count(&txn).await?;
+    println!("Benchmarking against {quote_count} quotes, {iterations} iterations each\n");
+
+    for (name, params) in scenarios(&txn).await? {
+        println!("{name}");
+
+        let order_by_random = time(iterations, || async {
+            txn.query_one(build_order_by_random_query(&params)).await?;
+            Ok(())
+        })
+        .await?;
+        report("order by random()", &order_by_random);
+
+        let mut rng = StdRng::from_entropy();
+        let by_position = time(iterations, || {
+            let rng = StdRng::from_rng(&mut rng).unwrap();
+            let params = &params;
+            let txn = &txn;
+            async move {
+                let mut rng = rng;
+                pick_random_quote_ids(txn, params, 1, &mut rng)
+                    .await
+                    .map_err(|e| format!("{:?}", e))?;
+                Ok(())
+            }
+        })
+        .await?;
+        report("pick by position", &by_position);
+        println!();
+    }
+
+    txn.rollback().await?;
+
+    Ok(())
+}
+
+/// Builds the filters to benchmark with, using a show and character that exist in the database.
+async fn scenarios(
+    txn: &DatabaseTransaction,
+) -> Result<Vec<(&'static str, GetRandomQuoteRequestParams)>, Error> {
+    let mut scenarios = vec![("No filters", GetRandomQuoteRequestParams::default())];
+
+    let show_name = entity::show::Entity::find()
+        .select_only()
+        .column(entity::show::Column::Name)
+        .into_tuple::<String>()
+        .one(txn)
+        .await?;
+    let character_name = entity::character::Entity::find()
+        .select_only()
+        .column(entity::character::Column::Name)
+        .into_tuple::<String>()
+        .one(txn)
+        .await?;
+
+    if let Some(show_name) = show_name {
+        let params = GetRandomQuoteRequestParams {
+            show_name: Some(show_name),
+            ..Default::default()
+        };
+        scenarios.push(("Show filter", params));
+    }
+    if let Some(character_name) = character_name {
+        let params = GetRandomQuoteRequestParams {
+            character_name: Some(character_name),
+            ..Default::default()
+        };
+        scenarios.push(("Character filter", params));
+    }
+
+    Ok(scenarios)
+}
+
+/// The query that was used to pick a random quote before `pick_random_quote_ids`.
+fn build_order_by_random_query(params: &GetRandomQuoteRequestParams) -> Statement {
+    let mut query = entity::quote::Entity::find()
+        .inner_join(entity::episode::Entity)
+        .inner_join(entity::season::Entity)
+        .inner_join(entity::show::Entity)
+        .inner_join(entity::quote_part::Entity)
+        .join(
+            JoinType::InnerJoin,
+            entity::character::Relation::QuotePart.def().rev(),
+        );
+
+    if let Some(show_name) = &params.show_name {
+        query = query.filter(entity::show::Column::Name.eq(show_name));
+    }
+    if let Some(character_name) = &params.character_name {
+        query = query.filter(entity::character::Column::Name.eq(character_name));
+    }
+
+    let query = query
+        .select_only()
+        .column_as(entity::quote::Column::Id, "quote_id")
+        .as_query()
+        .to_owned()
+        .order_by_expr(SimpleExpr::FunctionCall(Func::random()), Order::Asc)
+        .limit(1)
+        .to_owned();
+
+    sea_orm::DatabaseBackend::Postgres.build(&query)
+}
+
+/// Copies existing quotes, along with their parts, until `count` new quotes have been added.
+async fn add_extra_quotes(txn: &DatabaseTransaction, count: i64) -> Result<(), Error> {
+    let backend = txn.get_database_backend();
+
+    txn.execute(Statement::from_sql_and_values(
+        backend,
+        "
+        create temp table bench_quote on commit drop as
+        with source as (
+            select id, row_number() over (order by id) - 1 as position, count(*) over () as total
+            from quote
+        )
+        select (select max(id) from quote) + g as id, source.id as source_quote_id
+        from generate_series(1, $1) g
+        join source on source.position = g % source.total;
+        ",
+        [count.into()],
+    ))
+    .await?;
+
+    txn.execute_unprepared(
+        "
+        insert into quote (id, show_id, season_id, episode_id, source_id)
+        select b.id, q.show_id, q.season_id, q.episode_id, -b.id
+        from bench_quote b join quote q on q.id = b.source_quote_id;
+
+        insert into quote_part (quote_id, order_no, character_id, value)
+        select b.id, p.order_no, p.character_id, p.value
+        from bench_quote b join quote_part p on p.quote_id = b.source_quote_id;
+
+        analyze quote;
+        analyze quote_part;
+        ",
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Runs `f` the given number of times, returning how long each run took.
+async fn time<F, Fut>(iterations: usize, mut f: F) -> Result<Vec<Duration>, Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<(), Error>>,
+{
+    let mut timings = Vec::with_capacity(iterations);
+
+    for _ in 0..iterations {
+        let start = Instant::now();
+        f().await?;
+        timings.push(start.elapsed());
+    }
+
+    Ok(timings)
+}
+
+fn report(name: &str, timings: &[Duration]) {
+    let mut sorted = timings.to_vec();
+    sorted.sort();
+
+    let mean = sorted.iter().sum::<Duration>() / sorted.len() as u32;
+    let p95 = sorted[(sorted.len() * 95 / 100).min(sorted.len() - 1)];
+
+    println!("  {name:<20} mean {mean:>10.2?}  p95 {p95:>10.2?}");
+}
+
+fn get_env_var<T>(name: &str) -> Result<Option<T>, Error>
+where
+    T: std::str::FromStr,
+{
+    match env::var(name) {
+        Err(_) => Ok(None),
+        Ok(value) => Ok(Some(
+            value
+                .parse::<T>()
+                .map_err(|_| format!("Invalid value for {}", name))?,
+        )),
+    }
+}
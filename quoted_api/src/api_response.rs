@@ -1,8 +1,29 @@
+use chrono::{DateTime, Utc};
+use http::{
+    header::{
+        ACCEPT, ALLOW, CACHE_CONTROL, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, VARY,
+    },
+    HeaderMap, Method,
+};
+use quoted_format::{Format, Quotes};
 use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tracing::{debug, error};
+use utoipa::ToSchema;
+
+use crate::{
+    error::{ErrorCode, FieldError},
+    pipeline::cors::allow_header,
+};
 use vercel_runtime::{Body, Error, Response};
 
 pub type ApiResult<T> = Result<SuccessResult<T>, ErrorResult>;
 
+///
+/// The format of dates in HTTP headers.
+///
+const HTTP_DATE_FORMAT: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
 #[derive(Debug, Serialize)]
 pub struct SuccessResult<T>
 where
@@ -10,6 +31,69 @@ where
 {
     pub status_code: u16,
     pub body: T,
+    #[serde(skip)]
+    pub headers: Vec<(String, String)>,
+    #[serde(skip)]
+    pub cache: CachePolicy,
+    #[serde(skip)]
+    pub last_modified: Option<DateTime<Utc>>,
+    #[serde(skip)]
+    pub render: Option<Renderer<T>>,
+}
+
+///
+/// Renders the body of a response in a format other than JSON.
+///
+pub type Renderer<T> = fn(&T, Format) -> Result<String, quoted_format::Error>;
+
+///
+/// How long a response can be cached for, by browsers and by shared caches
+/// such as the Vercel edge network.
+///
+/// Cacheable responses are given a strong ETag, so that clients can revalidate
+/// them with `If-None-Match` and get a `304 Not Modified` when nothing has changed.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CachePolicy {
+    ///
+    /// The response must not be cached, e.g. because it's random or changes data.
+    ///
+    #[default]
+    NoStore,
+
+    ///
+    /// The response can be cached by browsers for `max_age` seconds, and by
+    /// shared caches for `shared_max_age` seconds.
+    ///
+    Public { max_age: i64, shared_max_age: i64 },
+}
+
+impl CachePolicy {
+    ///
+    /// For shows, seasons, episodes and characters, which rarely change.
+    ///
+    pub const CATALOGUE: CachePolicy = CachePolicy::Public {
+        max_age: 300,
+        shared_max_age: 3600,
+    };
+
+    ///
+    /// For quotes, which can be changed through the API at any time.
+    ///
+    pub const QUOTES: CachePolicy = CachePolicy::Public {
+        max_age: 60,
+        shared_max_age: 300,
+    };
+
+    fn header_value(&self) -> String {
+        return match self {
+            CachePolicy::NoStore => "no-store".to_owned(),
+            CachePolicy::Public {
+                max_age,
+                shared_max_age,
+            } => format!("public, max-age={max_age}, s-maxage={shared_max_age}"),
+        };
+    }
 }
 
 pub trait VercelResponse {
@@ -24,14 +108,136 @@ where
         SuccessResult {
             status_code: 200,
             body,
+            headers: vec![],
+            cache: CachePolicy::NoStore,
+            last_modified: None,
+            render: None,
         }
     }
     pub fn created(body: T) -> SuccessResult<T> {
         SuccessResult {
             status_code: 201,
             body,
+            headers: vec![],
+            cache: CachePolicy::NoStore,
+            last_modified: None,
+            render: None,
         }
     }
+    pub fn with_status(mut self, status_code: u16) -> Self {
+        self.status_code = status_code;
+        self
+    }
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_owned(), value.to_owned()));
+        self
+    }
+    pub fn with_cache(mut self, cache: CachePolicy) -> Self {
+        self.cache = cache;
+        self
+    }
+    pub fn with_last_modified(mut self, last_modified: DateTime<Utc>) -> Self {
+        self.last_modified = Some(last_modified);
+        self
+    }
+
+    ///
+    /// Builds the HTTP response in the format requested by the client, applying the cache policy.
+    ///
+    /// The format is taken from the `format` query parameter when there is one, and the `Accept`
+    /// header otherwise. Responses are JSON unless the handler allowed other formats with
+    /// `with_formats`, in which case the `Accept` header falls back to JSON for unsupported formats,
+    /// but the `format` parameter is rejected.
+    ///
+    /// When the response can be cached and the client already has it, as shown by
+    /// the `If-None-Match` (or failing that, `If-Modified-Since`) request header,
+    /// an empty `304 Not Modified` response is returned instead.
+    ///
+    pub fn respond(
+        self,
+        request_headers: &HeaderMap,
+        requested_format: Option<Format>,
+    ) -> Result<Response<Body>, Error> {
+        let format = match requested_format {
+            Some(format) => format,
+            None => request_headers
+                .get(ACCEPT)
+                .and_then(|accept| accept.to_str().ok())
+                .and_then(Format::from_accept)
+                .unwrap_or_default(),
+        };
+
+        let (format, content) = match (format, self.render) {
+            (format, Some(render)) => match render(&self.body, format) {
+                Err(e) => {
+                    error!(?format, error = %e, "Error rendering response");
+                    return ErrorResult::server_error("Error rendering response").vercel();
+                }
+                Ok(content) => (format, content),
+            },
+            (Format::Json, None) => (Format::Json, serde_json::to_string(&self.body)?),
+            (_, None) if requested_format.is_some() => {
+                return ErrorResult::bad_request("Format not supported by this endpoint")
+                    .with_field("format")
+                    .vercel();
+            }
+            (_, None) => (Format::Json, serde_json::to_string(&self.body)?),
+        };
+
+        let mut headers = self.headers;
+        headers.push((CACHE_CONTROL.to_string(), self.cache.header_value()));
+        if self.render.is_some() {
+            headers.push((VARY.to_string(), ACCEPT.to_string()));
+        }
+
+        if self.cache == CachePolicy::NoStore {
+            return build_response(self.status_code, format, content, &headers);
+        }
+
+        let etag = format!("\"{}\"", to_hex(&Sha256::digest(content.as_bytes())));
+        headers.push((ETAG.to_string(), etag.clone()));
+        if let Some(last_modified) = self.last_modified {
+            headers.push((
+                LAST_MODIFIED.to_string(),
+                last_modified.format(HTTP_DATE_FORMAT).to_string(),
+            ));
+        }
+
+        let not_modified = match request_headers.get(IF_NONE_MATCH) {
+            Some(if_none_match) => etag_matches(if_none_match.to_str().unwrap_or_default(), &etag),
+            None => match (request_headers.get(IF_MODIFIED_SINCE), self.last_modified) {
+                (Some(since), Some(last_modified)) => {
+                    not_modified_since(since.to_str().unwrap_or_default(), last_modified)
+                }
+                _ => false,
+            },
+        };
+
+        if not_modified {
+            debug!("Returning not modified");
+            let mut builder = Response::builder().status(304);
+            for (name, value) in headers {
+                builder = builder.header(name, value);
+            }
+            return Ok(builder.body(Body::Empty)?);
+        }
+
+        build_response(self.status_code, format, content, &headers)
+    }
+}
+
+impl<T> SuccessResult<T>
+where
+    T: Serialize + Quotes,
+{
+    ///
+    /// Allows the quotes in the response to be rendered as plain text, CSV or NDJSON,
+    /// as well as JSON.
+    ///
+    pub fn with_formats(mut self) -> Self {
+        self.render = Some(|body, format| quoted_format::render(body, format));
+        self
+    }
 }
 
 impl<T> VercelResponse for SuccessResult<T>
@@ -39,75 +245,225 @@ where
     T: Serialize,
 {
     fn vercel(self) -> Result<Response<Body>, Error> {
-        Ok(build_http_response(self.status_code, self.body))
+        self.respond(&HeaderMap::new(), None)
     }
 }
 
-#[derive(Debug, Serialize)]
+///
+/// The body returned when a request fails.
+///
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ErrorDetail {
+    ///
+    /// Why the request failed.
+    ///
+    pub code: ErrorCode,
+
+    ///
+    /// What went wrong, in a form that can be shown to users.
+    ///
     pub message: Option<String>,
-    pub key: Option<String>,
+
+    ///
+    /// The problems with individual parameters or body fields, if there were any.
+    ///
+    pub errors: Vec<FieldError>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct ErrorResult {
     pub status_code: u16,
     pub body: ErrorDetail,
+    #[serde(skip)]
+    pub headers: Vec<(String, String)>,
 }
 
 impl ErrorResult {
-    pub fn not_found() -> ErrorResult {
+    pub fn new(code: ErrorCode, message: &str) -> ErrorResult {
         ErrorResult {
+            status_code: code.status_code(),
             body: ErrorDetail {
-                key: None,
-                message: None,
+                code,
+                message: Some(message.to_owned()),
+                errors: vec![],
             },
-            status_code: 404,
+            headers: vec![],
         }
     }
+    pub fn not_found() -> ErrorResult {
+        ErrorResult::new(ErrorCode::NotFound, "Not found")
+    }
     pub fn bad_request(message: &str) -> ErrorResult {
-        ErrorResult {
-            body: ErrorDetail {
-                key: None,
-                message: Some(message.to_owned()),
-            },
-            status_code: 400,
-        }
+        ErrorResult::new(ErrorCode::BadRequest, message)
+    }
+    pub fn unauthorized(message: &str) -> ErrorResult {
+        ErrorResult::new(ErrorCode::Unauthorized, message)
+    }
+    pub fn forbidden(message: &str) -> ErrorResult {
+        ErrorResult::new(ErrorCode::Forbidden, message)
     }
     pub fn server_error(message: &str) -> ErrorResult {
-        ErrorResult {
-            body: ErrorDetail {
-                key: None,
-                message: Some(message.to_owned()),
-            },
-            status_code: 500,
-        }
+        ErrorResult::new(ErrorCode::InternalError, message)
+    }
+    pub fn method_not_allowed(allowed: &[Method]) -> ErrorResult {
+        ErrorResult::new(ErrorCode::MethodNotAllowed, "Method not allowed")
+            .with_header(ALLOW.as_str(), &allow_header(allowed))
     }
-    pub fn with_key(mut self, key: &str) -> Self {
-        self.body.key = Some(key.to_owned());
+
+    ///
+    /// Reports the message of the error against the given parameter or body field.
+    ///
+    pub fn with_field(self, field: &str) -> Self {
+        let message = self.body.message.clone().unwrap_or_default();
+        self.with_field_error(FieldError {
+            field: field.to_owned(),
+            message,
+        })
+    }
+    pub fn with_field_error(mut self, error: FieldError) -> Self {
+        self.body.errors.push(error);
         self
     }
     pub fn with_message(mut self, message: &str) -> Self {
         self.body.message = Some(message.to_owned());
         self
     }
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_owned(), value.to_owned()));
+        self
+    }
 }
 
 impl VercelResponse for ErrorResult {
     fn vercel(self) -> Result<Response<Body>, vercel_runtime::Error> {
-        Ok(build_http_response(self.status_code, self.body))
+        let json = serde_json::to_string(&self.body)?;
+        let mut headers = self.headers;
+        headers.push((
+            CACHE_CONTROL.to_string(),
+            CachePolicy::NoStore.header_value(),
+        ));
+
+        build_response(self.status_code, Format::Json, json, &headers)
     }
 }
 
-fn build_http_response<T>(status_code: u16, body: T) -> Response<Body>
-where
-    T: Serialize,
-{
-    let json = serde_json::to_string(&body).unwrap();
+/// Checks whether any of the ETags in an `If-None-Match` header match the ETag of the response.
+/// Weak comparison is used, as the header is only used for `GET` requests.
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match.split(',').map(|tag| tag.trim()).any(|tag| {
+        let tag = tag.strip_prefix("W/").unwrap_or(tag);
+        tag == "*" || tag == etag
+    })
+}
+
+fn not_modified_since(if_modified_since: &str, last_modified: DateTime<Utc>) -> bool {
+    return match DateTime::parse_from_rfc2822(if_modified_since) {
+        Err(_) => false,
+        Ok(since) => last_modified.timestamp() <= since.timestamp(),
+    };
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
 
-    Response::builder()
+fn build_response(
+    status_code: u16,
+    format: Format,
+    content: String,
+    headers: &[(String, String)],
+) -> Result<Response<Body>, Error> {
+    let mut builder = Response::builder()
         .status(status_code)
-        .header("Content-Type", "application/json")
-        .body(Body::Text(json))
-        .unwrap()
+        .header("Content-Type", format.content_type());
+    for (name, value) in headers {
+        builder = builder.header(name, value);
+    }
+
+    Ok(builder.body(Body::Text(content))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use http::HeaderValue;
+
+    const ETAG_VALUE: &str = "\"abc\"";
+
+    #[test]
+    fn etags_match_exactly() {
+        assert!(etag_matches("\"abc\"", ETAG_VALUE));
+        assert!(!etag_matches("\"abd\"", ETAG_VALUE));
+        assert!(!etag_matches("abc", ETAG_VALUE));
+    }
+
+    #[test]
+    fn etags_match_any_in_a_list() {
+        assert!(etag_matches("\"xyz\", \"abc\"", ETAG_VALUE));
+        assert!(etag_matches("\"xyz\",\"abc\"", ETAG_VALUE));
+        assert!(!etag_matches("\"xyz\", \"uvw\"", ETAG_VALUE));
+    }
+
+    #[test]
+    fn weak_etags_match() {
+        assert!(etag_matches("W/\"abc\"", ETAG_VALUE));
+        assert!(etag_matches("\"xyz\", W/\"abc\"", ETAG_VALUE));
+    }
+
+    #[test]
+    fn wildcard_matches_any_etag() {
+        assert!(etag_matches("*", ETAG_VALUE));
+    }
+
+    #[test]
+    fn not_modified_since_the_last_modified_time_or_later() {
+        let last_modified = Utc.with_ymd_and_hms(2024, 11, 2, 9, 0, 0).unwrap();
+
+        assert!(not_modified_since(
+            "Sat, 02 Nov 2024 09:00:00 GMT",
+            last_modified
+        ));
+        assert!(not_modified_since(
+            "Sat, 02 Nov 2024 10:00:00 GMT",
+            last_modified
+        ));
+        assert!(!not_modified_since(
+            "Sat, 02 Nov 2024 08:59:59 GMT",
+            last_modified
+        ));
+    }
+
+    #[test]
+    fn invalid_dates_are_modified() {
+        let last_modified = Utc.with_ymd_and_hms(2024, 11, 2, 9, 0, 0).unwrap();
+
+        assert!(!not_modified_since("yesterday", last_modified));
+        assert!(!not_modified_since("", last_modified));
+    }
+
+    #[test]
+    fn cached_responses_are_not_modified_when_the_etag_matches() {
+        let respond = |if_none_match: Option<&str>| {
+            let mut headers = HeaderMap::new();
+            if let Some(if_none_match) = if_none_match {
+                headers.insert(IF_NONE_MATCH, HeaderValue::from_str(if_none_match).unwrap());
+            }
+            SuccessResult::ok(vec![1, 2, 3])
+                .with_cache(CachePolicy::QUOTES)
+                .respond(&headers, None)
+                .unwrap()
+        };
+
+        let res = respond(None);
+        assert_eq!(res.status(), 200);
+        let etag = res.headers()[ETAG].to_str().unwrap().to_owned();
+
+        let res = respond(Some(&etag));
+        assert_eq!(res.status(), 304);
+        assert!(matches!(res.body(), Body::Empty));
+        assert_eq!(res.headers()[ETAG], etag.as_str());
+
+        assert_eq!(respond(Some("\"other\"")).status(), 200);
+    }
 }
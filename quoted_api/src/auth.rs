@@ -0,0 +1,212 @@
+use argon2::{
+    password_hash::{
+        rand_core::{OsRng, RngCore},
+        PasswordHash, PasswordHasher, PasswordVerifier, SaltString,
+    },
+    Argon2,
+};
+use chrono::{Duration, Utc};
+use http::{header::AUTHORIZATION, HeaderMap};
+use quoted_api_models::auth::{Role, User};
+use quoted_db_entity::{user_token, users};
+use sea_orm::{
+    entity::*,
+    prelude::{DateTimeWithTimeZone, Uuid},
+    query::*,
+    ConnectionTrait, DbErr, EntityTrait,
+};
+use sha2::{Digest, Sha256};
+use tracing::{error, warn};
+
+use crate::{
+    api_response::ErrorResult,
+    pipeline::{db_error, Context},
+};
+
+///
+/// How long a token is valid for after it's issued.
+///
+const TOKEN_LIFETIME_HOURS: i64 = 24;
+
+///
+/// Hashes the password with argon2, returning the hash in the PHC string format
+/// so that it can be stored against the user.
+///
+pub fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default().hash_password(password.as_bytes(), &salt)?;
+
+    return Ok(hash.to_string());
+}
+
+///
+/// A hash of a password that no user has, created by `hash_password`. Passwords are
+/// checked against it when the user doesn't exist, so that logging in takes as long
+/// whether or not the user exists.
+///
+const DUMMY_PASSWORD_HASH: &str =
+    "$argon2id$v=19$m=19456,t=2,p=1$qgESKGz474PeWWzCwt9ozw$t0mjADdbN+rqqPse93avwewjCsA47NpOZCR3FkqeMQ8";
+
+///
+/// Checks the password against a hash created by `hash_password`.
+///
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    return match PasswordHash::new(password_hash) {
+        Err(e) => {
+            error!(error = %e, "Invalid password hash");
+            false
+        }
+        Ok(hash) => Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok(),
+    };
+}
+
+///
+/// Checks the password against `DUMMY_PASSWORD_HASH`, which never matches, taking
+/// as long as checking a real user's password would.
+///
+pub fn verify_dummy_password(password: &str) -> bool {
+    verify_password(password, DUMMY_PASSWORD_HASH)
+}
+
+///
+/// Issues a new token for the user, returning the token and when it expires.
+///
+/// Only a hash of the token is stored, so the token can't be recovered from
+/// the database. Any of the user's tokens that have expired are removed.
+///
+pub async fn issue_token<C>(db: &C, user_id: Uuid) -> Result<(String, DateTimeWithTimeZone), DbErr>
+where
+    C: ConnectionTrait,
+{
+    let now = Utc::now().fixed_offset();
+
+    user_token::Entity::delete_many()
+        .filter(user_token::Column::UserId.eq(user_id))
+        .filter(user_token::Column::ExpiresAt.lte(now))
+        .exec(db)
+        .await?;
+
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    let token = to_hex(&bytes);
+    let expires_at = now + Duration::hours(TOKEN_LIFETIME_HOURS);
+
+    let model = user_token::ActiveModel {
+        id: NotSet,
+        user_id: Set(user_id),
+        token_hash: Set(hash_token(&token)),
+        created_at: Set(now),
+        expires_at: Set(expires_at),
+    };
+    user_token::Entity::insert(model).exec(db).await?;
+
+    return Ok((token, expires_at));
+}
+
+///
+/// Checks that the request was made by a user with at least the given role,
+/// returning the user.
+///
+/// Handlers that need to be protected should call this before doing anything else.
+///
+/// # Errors
+///
+/// Unauthorized when the request has no valid bearer token, or forbidden when
+/// the user doesn't have the role.
+///
+pub async fn require_role(ctx: &Context, role: Role) -> Result<User, ErrorResult> {
+    let token = bearer_token(&ctx.headers)
+        .ok_or_else(|| ErrorResult::unauthorized("Missing bearer token"))?;
+
+    let user = find_token_user(&ctx.db, token)
+        .await
+        .map_err(|e| db_error(e, "Error checking token"))?;
+
+    let user = match user {
+        None => return Err(ErrorResult::unauthorized("Invalid or expired token")),
+        Some(user) => to_api_user(user)?,
+    };
+
+    if user.role < role {
+        warn!(user_id = %user.id, role = role.as_str(), "User does not have the role");
+        return Err(ErrorResult::forbidden("Insufficient permissions"));
+    }
+
+    return Ok(user);
+}
+
+///
+/// Gets the bearer token from the `Authorization` header, if there is one.
+///
+pub fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| token.trim())
+        .filter(|token| !token.is_empty())
+}
+
+///
+/// Finds the user that the token was issued to, if the token is valid and hasn't expired.
+///
+pub async fn find_token_user<C>(db: &C, token: &str) -> Result<Option<users::Model>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    users::Entity::find()
+        .inner_join(user_token::Entity)
+        .filter(user_token::Column::TokenHash.eq(hash_token(token)))
+        .filter(user_token::Column::ExpiresAt.gt(Utc::now().fixed_offset()))
+        .one(db)
+        .await
+}
+
+///
+/// Converts a user from the database into the shape returned by the API.
+///
+pub fn to_api_user(user: users::Model) -> Result<User, ErrorResult> {
+    let role = user.role.parse::<Role>().map_err(|e| {
+        error!(error = %e, "Invalid user role");
+        ErrorResult::server_error("Invalid user role")
+    })?;
+
+    return Ok(User {
+        id: user.id.to_string(),
+        name: user.name,
+        email: user.email,
+        role,
+    });
+}
+
+fn hash_token(token: &str) -> String {
+    to_hex(&Sha256::digest(token.as_bytes()))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dummy_hash_uses_the_same_params_as_hash_password() {
+        let dummy = PasswordHash::new(DUMMY_PASSWORD_HASH).unwrap();
+        let hash = hash_password("password").unwrap();
+        let real = PasswordHash::new(&hash).unwrap();
+
+        assert_eq!(dummy.algorithm, real.algorithm);
+        assert_eq!(dummy.version, real.version);
+        assert_eq!(dummy.params, real.params);
+    }
+
+    #[test]
+    fn dummy_hash_never_matches() {
+        assert!(!verify_dummy_password(""));
+        assert!(!verify_dummy_password("password"));
+    }
+}
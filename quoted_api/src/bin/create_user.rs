@@ -0,0 +1,58 @@
+use std::{env, io};
+
+use quoted_api::auth::hash_password;
+use quoted_api_models::auth::Role;
+use quoted_db::get_default_connection;
+use quoted_db_entity::users;
+use sea_orm::{prelude::Uuid, EntityTrait, Set};
+use vercel_runtime::Error;
+
+const USAGE: &str = "Usage: create-user <name> <email> <admin|contributor>";
+
+///
+/// Creates a user that can log in to the API, e.g. to set up the first admin.
+///
+/// The password is read from stdin so that it doesn't end up in the shell history:
+///
+/// ```sh
+/// cargo run -p quoted_api --bin create-user -- "Jane" jane@example.com admin
+/// ```
+///
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    let _ = dotenvy::dotenv();
+
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    let [name, email, role] = args.as_slice() else {
+        return Err(USAGE.into());
+    };
+    let role = role
+        .parse::<Role>()
+        .map_err(|e| format!("{}\n{}", e, USAGE))?;
+
+    println!("Enter a password for {email}:");
+    let mut password = String::new();
+    io::stdin().read_line(&mut password)?;
+    let password = password.trim_end_matches(['\r', '\n']);
+    if password.is_empty() {
+        return Err("A password is required".into());
+    }
+
+    let password_hash = hash_password(password).map_err(|e| e.to_string())?;
+
+    println!("Getting DB Connection");
+    let db = get_default_connection().await?;
+
+    let user = users::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        name: Set(name.to_owned()),
+        email: Set(email.trim().to_owned()),
+        password: Set(password_hash),
+        role: Set(role.as_str().to_owned()),
+    };
+    users::Entity::insert(user).exec(&db).await?;
+
+    println!("Created {} {}", role.as_str(), email);
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use std::{env, net::SocketAddr};
+
+use quoted_api::{runtime::local, setup::setup};
+use quoted_db::get_default_connection;
+use tracing::info;
+use vercel_runtime::Error;
+
+const DEFAULT_PORT: u16 = 3000;
+const PORT_ENV_VAR: &str = "PORT";
+
+///
+/// Runs every API endpoint behind a single local HTTP server, so the API can be
+/// used without the Vercel CLI. Only a reachable postgres database is required.
+///
+/// The port defaults to `3000` and can be changed with the `PORT` env var.
+///
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    let _ = dotenvy::dotenv();
+    setup();
+
+    info!("Getting DB Connection");
+    let db = get_default_connection().await?;
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], get_port()?));
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+
+    info!("Listening on http://{addr}");
+    axum::serve(listener, local::router(db)).await?;
+
+    Ok(())
+}
+
+/// Gets the port to listen on from the `PORT` env var, falling back to `3000`.
+///
+/// # Errors
+///
+/// If the env var is set but is not a valid port number.
+fn get_port() -> Result<u16, Error> {
+    match env::var(PORT_ENV_VAR) {
+        Err(_) => Ok(DEFAULT_PORT),
+        Ok(port) => Ok(port
+            .parse::<u16>()
+            .map_err(|_| format!("Invalid value for {}", PORT_ENV_VAR))?),
+    }
+}
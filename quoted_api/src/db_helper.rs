@@ -0,0 +1,204 @@
+use quoted_api_models::quote::QuotePart;
+use quoted_db_entity::{character, character_show, episode, quote, quote_part, season, show};
+use sea_orm::{
+    entity::*,
+    query::*,
+    sea_query::{Expr, Func, IntoCondition},
+    ConnectionTrait, DbErr, EntityTrait, RelationDef,
+};
+use tracing::debug;
+
+///
+/// The IDs of the show, season and episode that a quote belongs to.
+///
+pub struct QuoteLocation {
+    pub show_id: i32,
+    pub season_id: i32,
+    pub episode_id: i32,
+}
+
+///
+/// Joins the quote of each part, as long as it's from the joined show.
+///
+/// Characters are linked to the shows they appear in through `character_show`,
+/// and a character may appear in multiple shows, so this is used to only count
+/// the quotes from the show.
+///
+pub fn quote_in_show() -> RelationDef {
+    quote_part::Relation::Quote
+        .def()
+        .on_condition(|_left, right| {
+            Expr::col((right, quote::Column::ShowId))
+                .equals((show::Entity, show::Column::Id))
+                .into_condition()
+        })
+}
+
+///
+/// Prevents other writers from allocating IDs until the current transaction ends.
+///
+/// IDs are allocated the same way the seeder does it, by taking the current
+/// max ID plus one, rather than relying on the sequences. Reads are not blocked.
+///
+pub async fn lock_for_id_allocation<C>(db: &C) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    db.execute_unprepared("LOCK TABLE quote, character IN SHARE ROW EXCLUSIVE MODE")
+        .await?;
+    Ok(())
+}
+
+///
+/// Gets the next ID to be used for the entity `E`.
+///
+pub async fn next_id<E, C>(db: &C, id_column: E::Column) -> Result<i32, DbErr>
+where
+    E: EntityTrait,
+    C: ConnectionTrait,
+{
+    let max = E::find()
+        .select_only()
+        .expr(Func::coalesce([
+            Expr::col(id_column).max(),
+            Expr::val(0).into(),
+        ]))
+        .into_tuple::<i32>()
+        .one(db)
+        .await?;
+
+    return Ok(max.unwrap_or(0) + 1);
+}
+
+///
+/// Finds the IDs of the show, season and episode with the given names and numbers.
+///
+pub async fn find_quote_location<C>(
+    db: &C,
+    show_name: &str,
+    season_no: i32,
+    episode_no: i32,
+) -> Result<Option<QuoteLocation>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let ids = episode::Entity::find()
+        .inner_join(season::Entity)
+        .inner_join(show::Entity)
+        .select_only()
+        .column_as(show::Column::Id, "show_id")
+        .column_as(season::Column::Id, "season_id")
+        .column_as(episode::Column::Id, "episode_id")
+        .filter(show::Column::Name.eq(show_name))
+        .filter(season::Column::SeasonNo.eq(season_no))
+        .filter(episode::Column::EpisodeNo.eq(episode_no))
+        .into_tuple::<(i32, i32, i32)>()
+        .one(db)
+        .await?;
+
+    return Ok(ids.map(|(show_id, season_id, episode_id)| QuoteLocation {
+        show_id,
+        season_id,
+        episode_id,
+    }));
+}
+
+///
+/// Gets the ID of the character in the show, creating the character if it doesn't exist.
+///
+pub async fn create_character_for_show<C>(
+    db: &C,
+    show_id: i32,
+    character_name: &str,
+) -> Result<i32, DbErr>
+where
+    C: ConnectionTrait,
+{
+    debug!(show_id, character_name, "Creating character for show");
+    let existing_id = character::Entity::find()
+        .select_only()
+        .column(character::Column::Id)
+        .join(
+            JoinType::InnerJoin,
+            character_show::Relation::Character.def().rev(),
+        )
+        .filter(
+            character::Column::Name
+                .eq(character_name)
+                .and(character_show::Column::ShowId.eq(show_id)),
+        )
+        .into_tuple::<i32>()
+        .one(db)
+        .await?;
+
+    if let Some(id) = existing_id {
+        return Ok(id);
+    }
+
+    let character_id = next_id::<character::Entity, _>(db, character::Column::Id).await?;
+    debug!(character_id, "Using next character id");
+
+    let character = character::ActiveModel {
+        id: Set(character_id),
+        name: Set(character_name.to_owned()),
+        show_id: Set(show_id),
+    };
+    character::Entity::insert(character).exec(db).await?;
+
+    let character_show = character_show::ActiveModel {
+        character_id: Set(character_id),
+        show_id: Set(show_id),
+    };
+    character_show::Entity::insert(character_show)
+        .exec(db)
+        .await?;
+
+    return Ok(character_id);
+}
+
+///
+/// Inserts the parts of a quote, creating any characters that don't yet exist in the show.
+///
+pub async fn create_quote_parts<C>(
+    db: &C,
+    quote_id: i32,
+    show_id: i32,
+    parts: &[QuotePart],
+) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    let mut models = vec![];
+
+    for part in parts {
+        let character_id =
+            create_character_for_show(db, show_id, part.character_name.trim()).await?;
+
+        models.push(quote_part::ActiveModel {
+            id: NotSet,
+            quote_id: Set(quote_id),
+            order_no: Set(part.order),
+            character_id: Set(character_id),
+            value: Set(part.quote_text.clone()),
+        });
+    }
+
+    quote_part::Entity::insert_many(models).exec(db).await?;
+
+    return Ok(());
+}
+
+///
+/// Deletes every part of the quote.
+///
+pub async fn delete_quote_parts<C>(db: &C, quote_id: i32) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    quote_part::Entity::delete_many()
+        .filter(quote_part::Column::QuoteId.eq(quote_id))
+        .exec(db)
+        .await?;
+
+    return Ok(());
+}
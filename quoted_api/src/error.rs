@@ -0,0 +1,231 @@
+use quoted_api_models::validate::ValidationError;
+use quoted_db::error::DBError;
+use sea_orm::{DbErr, SqlErr};
+use serde::Serialize;
+use thiserror::Error;
+use tracing::{debug, error};
+use utoipa::ToSchema;
+
+use crate::api_response::ErrorResult;
+
+///
+/// A stable, machine readable code describing why a request failed.
+/// Clients should rely on this rather than the message, which may change.
+///
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    ///
+    /// The request can't be handled as it is, e.g. no quotes match the filters.
+    ///
+    BadRequest,
+
+    ///
+    /// The query string couldn't be parsed into the parameters of the endpoint.
+    ///
+    InvalidParameters,
+
+    ///
+    /// The body is missing, or couldn't be parsed.
+    ///
+    InvalidBody,
+
+    ///
+    /// The request was parsed, but some of the values aren't allowed.
+    ///
+    ValidationFailed,
+
+    ///
+    /// The request needs a valid bearer token.
+    ///
+    Unauthorized,
+
+    ///
+    /// The user doesn't have the role needed.
+    ///
+    Forbidden,
+
+    ///
+    /// The endpoint or the item requested doesn't exist.
+    ///
+    NotFound,
+
+    ///
+    /// The endpoint doesn't support the HTTP method. The methods it supports are
+    /// in the `Allow` header.
+    ///
+    MethodNotAllowed,
+
+    ///
+    /// The change conflicts with data that already exists.
+    ///
+    Conflict,
+
+    ///
+    /// The client has made too many requests. The request can be tried again
+    /// after the number of seconds in the `Retry-After` header.
+    ///
+    RateLimited,
+
+    ///
+    /// The database couldn't be reached. The request can be tried again later.
+    ///
+    DatabaseUnavailable,
+
+    ///
+    /// The database returned an error.
+    ///
+    DatabaseError,
+
+    ///
+    /// Something else went wrong on the server.
+    ///
+    InternalError,
+}
+
+impl ErrorCode {
+    ///
+    /// The HTTP status code returned with the error code.
+    ///
+    pub fn status_code(&self) -> u16 {
+        return match self {
+            ErrorCode::BadRequest
+            | ErrorCode::InvalidParameters
+            | ErrorCode::InvalidBody
+            | ErrorCode::ValidationFailed => 400,
+            ErrorCode::Unauthorized => 401,
+            ErrorCode::Forbidden => 403,
+            ErrorCode::NotFound => 404,
+            ErrorCode::MethodNotAllowed => 405,
+            ErrorCode::Conflict => 409,
+            ErrorCode::RateLimited => 429,
+            ErrorCode::DatabaseUnavailable => 503,
+            ErrorCode::DatabaseError | ErrorCode::InternalError => 500,
+        };
+    }
+}
+
+///
+/// A problem with a single parameter or body field of the request.
+///
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct FieldError {
+    ///
+    /// The name of the parameter or field, e.g. `season_no` or `parts[0].quote_text`.
+    ///
+    pub field: String,
+
+    ///
+    /// What's wrong with the value.
+    ///
+    pub message: String,
+}
+
+///
+/// Errors from the libraries used by handlers, which are converted into an
+/// `ErrorResult` with the matching `ErrorCode`.
+///
+#[derive(Error, Debug)]
+pub enum ApiError {
+    #[error("Invalid parameters")]
+    Query(#[from] serde_path_to_error::Error<serde_urlencoded::de::Error>),
+
+    #[error("Invalid request body")]
+    Body(#[from] serde_path_to_error::Error<serde_json::Error>),
+
+    #[error("Database error")]
+    Database(#[from] DbErr),
+
+    #[error("Database connection error")]
+    Connection(#[from] DBError),
+}
+
+impl From<ApiError> for ErrorResult {
+    fn from(error: ApiError) -> Self {
+        match &error {
+            ApiError::Query(_) | ApiError::Body(_) => debug!(%error, "Invalid request"),
+            _ => error!(?error, "Request failed"),
+        }
+
+        return match error {
+            ApiError::Query(e) => {
+                let field = field_error(e.path(), &e.inner().to_string());
+                ErrorResult::new(ErrorCode::InvalidParameters, "Invalid parameters")
+                    .with_field_error(field)
+            }
+            ApiError::Body(e) => {
+                let field = field_error(e.path(), &e.inner().to_string());
+                ErrorResult::new(ErrorCode::InvalidBody, "Invalid request body")
+                    .with_field_error(field)
+            }
+            ApiError::Database(e) => database_error(&e),
+            ApiError::Connection(DBError::Connect(e)) => database_error(&e),
+            ApiError::Connection(_) => ErrorResult::new(
+                ErrorCode::InternalError,
+                "The database connection isn't configured correctly",
+            ),
+        };
+    }
+}
+
+impl From<Vec<ValidationError>> for ErrorResult {
+    fn from(errors: Vec<ValidationError>) -> Self {
+        debug!(?errors, "Validation failed");
+
+        errors.into_iter().fold(
+            ErrorResult::new(ErrorCode::ValidationFailed, "The request is invalid"),
+            |result, error| {
+                result.with_field_error(FieldError {
+                    field: error.field,
+                    message: error.message,
+                })
+            },
+        )
+    }
+}
+
+fn database_error(e: &DbErr) -> ErrorResult {
+    if let DbErr::Conn(_) | DbErr::ConnectionAcquire(_) = e {
+        return ErrorResult::new(
+            ErrorCode::DatabaseUnavailable,
+            "The database is unavailable",
+        );
+    }
+    if let DbErr::RecordNotFound(_) = e {
+        return ErrorResult::not_found();
+    }
+
+    return match e.sql_err() {
+        Some(SqlErr::UniqueConstraintViolation(_)) => {
+            ErrorResult::new(ErrorCode::Conflict, "Conflicts with existing data")
+        }
+        Some(SqlErr::ForeignKeyConstraintViolation(_)) => {
+            ErrorResult::new(ErrorCode::Conflict, "Refers to data that doesn't exist")
+        }
+        _ => ErrorResult::new(ErrorCode::DatabaseError, "Database error"),
+    };
+}
+
+///
+/// Builds the field error for a deserialization error at `path`.
+///
+/// Missing fields are reported by their parent, so the name of the field is
+/// taken from the message instead, e.g. "missing field `q`".
+///
+fn field_error(path: &serde_path_to_error::Path, message: &str) -> FieldError {
+    let missing = message
+        .strip_prefix("missing field `")
+        .and_then(|rest| rest.split('`').next());
+
+    let parent = path.to_string();
+    let field = match (parent.as_str(), missing) {
+        (".", Some(missing)) => missing.to_owned(),
+        (_, Some(missing)) => format!("{}.{}", parent, missing),
+        (_, None) => parent,
+    };
+
+    FieldError {
+        field,
+        message: message.to_owned(),
+    }
+}
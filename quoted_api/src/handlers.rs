@@ -0,0 +1,16 @@
+pub mod characters;
+pub mod create_quote;
+pub mod daily_quote;
+pub mod delete_quote;
+pub mod episodes;
+pub mod health;
+pub mod login;
+pub mod openapi;
+pub mod quote;
+pub mod quotes;
+pub mod random_quote;
+pub mod search_quotes;
+pub mod seasons;
+pub mod shows;
+pub mod stats;
+pub mod update_quote;
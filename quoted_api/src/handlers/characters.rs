@@ -0,0 +1,143 @@
+use crate::{
+    api_response::{ApiResult, CachePolicy, SuccessResult},
+    db_helper::quote_in_show,
+    pipeline::{
+        fetch_all,
+        pagination::{count_total, into_page, reject_cursor, Paginate},
+        Context, Handler,
+    },
+};
+use quoted_api_models::character::{
+    GetCharactersInShowRequest, GetCharactersInShowResponse, GetCharactersInShowResponseItem,
+};
+use quoted_db_entity as entity;
+use sea_orm::{
+    prelude::Expr,
+    sea_query::{extension::postgres::PgExpr, Alias, LikeExpr},
+    ColumnTrait, ConnectionTrait, DatabaseBackend, JoinType, QueryFilter, Statement,
+};
+use sea_orm::{EntityTrait, QueryOrder, QuerySelect, QueryTrait, Select};
+use tracing::debug;
+
+///
+/// Lists the characters within a show.
+///
+pub struct GetCharactersInShow;
+
+impl Handler for GetCharactersInShow {
+    type Params = GetCharactersInShowRequest;
+    type Response = GetCharactersInShowResponse;
+
+    async fn call(
+        params: GetCharactersInShowRequest,
+        ctx: &Context,
+    ) -> ApiResult<GetCharactersInShowResponse> {
+        reject_cursor(&params.cursor)?;
+
+        let stmt = build_query(&params, ctx.db.get_database_backend());
+        let characters =
+            fetch_all::<GetCharactersInShowResponseItem>(&ctx.db, stmt, "Error finding characters")
+                .await?;
+
+        let total =
+            count_total(&ctx.db, matching_characters(&params), params.include_total).await?;
+
+        debug!("Returning result");
+        Ok(
+            SuccessResult::ok(into_page(params.page, params.limit, characters).with_total(total))
+                .with_cache(CachePolicy::CATALOGUE),
+        )
+    }
+}
+
+fn build_query(request: &GetCharactersInShowRequest, db_backend: DatabaseBackend) -> Statement {
+    // Quotes are counted once, no matter how many parts of the quote the character is involved in
+    let query = matching_characters(request)
+        .select_only()
+        .column(entity::character::Column::Name)
+        .column_as(
+            Expr::col((entity::quote::Entity, entity::quote::Column::Id))
+                .count_distinct()
+                .cast_as(Alias::new("integer")),
+            "quote_count",
+        )
+        .left_join(entity::quote_part::Entity)
+        .join(JoinType::LeftJoin, quote_in_show())
+        .group_by(entity::character::Column::Id)
+        .group_by(entity::character::Column::Name)
+        .order_by_asc(entity::character::Column::Name)
+        .order_by_asc(entity::character::Column::Id)
+        .paginate(request.page, request.limit)
+        .as_query()
+        .to_owned();
+
+    db_backend.build(&query)
+}
+
+/// Selects the characters that match the filters in the request.
+fn matching_characters(request: &GetCharactersInShowRequest) -> Select<entity::character::Entity> {
+    let mut query = entity::character::Entity::find()
+        .inner_join(entity::show::Entity)
+        .filter(entity::show::Column::Name.eq(&request.query.show));
+
+    if let Some(name) = &request.query.name {
+        // Qualified, as the show is joined and also has a name column
+        query = query.filter(
+            Expr::col((entity::character::Entity, entity::character::Column::Name))
+                .ilike(LikeExpr::new(format!("%{}%", escape_like(name))).escape('\\')),
+        );
+    }
+
+    query
+}
+
+/// Escapes the wildcards in `text`, so that it's matched literally within a `LIKE` pattern.
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quoted_api_models::character::GetCharactersInShowRequestParams;
+
+    fn request(name: Option<&str>) -> GetCharactersInShowRequest {
+        GetCharactersInShowRequest {
+            query: GetCharactersInShowRequestParams {
+                show: "Futurama".to_owned(),
+                name: name.map(str::to_owned),
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn name_search_uses_the_character_name() {
+        let stmt = build_query(&request(Some("e")), DatabaseBackend::Postgres);
+
+        assert!(stmt.sql.contains(r#""character"."name" ILIKE ($2"#));
+        assert!(!stmt.sql.contains(r#"WHERE "name""#));
+        assert!(!stmt.sql.contains(r#"AND "name""#));
+    }
+
+    #[test]
+    fn name_search_matches_wildcards_literally() {
+        let stmt = build_query(&request(Some(r"100%_\")), DatabaseBackend::Postgres);
+
+        assert!(stmt
+            .sql
+            .contains(r#""character"."name" ILIKE ($2 ESCAPE E'\\')"#));
+        assert_eq!(stmt.values.unwrap().0[1], r"%100\%\_\\%".into());
+    }
+
+    #[test]
+    fn characters_are_found_through_character_show() {
+        let stmt = build_query(&request(None), DatabaseBackend::Postgres);
+
+        assert!(stmt.sql.contains(r#"INNER JOIN "character_show""#));
+        assert!(stmt.sql.contains(r#""show"."name" = $1"#));
+        assert!(!stmt.sql.contains(r#""character"."show_id""#));
+    }
+}
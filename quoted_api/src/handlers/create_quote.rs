@@ -0,0 +1,109 @@
+use crate::{
+    api_response::{ApiResult, ErrorResult, SuccessResult},
+    auth::require_role,
+    db_helper::{
+        create_quote_parts, find_quote_location, lock_for_id_allocation, next_id, QuoteLocation,
+    },
+    pipeline::{db_error, Context, Handler, NoParams},
+};
+use http::Method;
+use quoted_api_models::{
+    auth::Role,
+    quote::{CreateQuoteRequest, CreateQuoteResponse, SaveQuoteRequestBody},
+};
+use quoted_db_entity as entity;
+use sea_orm::{entity::*, ConnectionTrait, DatabaseConnection, TransactionTrait};
+use tracing::info;
+
+use super::quote::find_quote;
+
+///
+/// Creates a quote, along with any characters in it that don't yet exist in the show.
+/// Requires the contributor role.
+///
+pub struct CreateQuote;
+
+impl Handler for CreateQuote {
+    type Params = NoParams;
+    type Response = CreateQuoteResponse;
+
+    const METHOD: Method = Method::POST;
+
+    async fn call(_params: NoParams, ctx: &Context) -> ApiResult<CreateQuoteResponse> {
+        require_role(ctx, Role::Contributor).await?;
+
+        let request = ctx.json::<CreateQuoteRequest>()?;
+
+        let quote_id = save(&ctx.db, &request).await?;
+
+        return match find_quote(&ctx.db, quote_id).await? {
+            None => Err(ErrorResult::server_error("Error creating quote")),
+            Some(quote) => Ok(SuccessResult::created(quote)),
+        };
+    }
+}
+
+/// Inserts the quote and its parts in a single transaction, returning the ID of the new quote.
+async fn save(db: &DatabaseConnection, request: &CreateQuoteRequest) -> Result<i32, ErrorResult> {
+    let save_error = |e| db_error(e, "Error creating quote");
+
+    let txn = db.begin().await.map_err(save_error)?;
+    lock_for_id_allocation(&txn).await.map_err(save_error)?;
+
+    let location = find_location(&txn, request).await?;
+
+    let quote_id = next_id::<entity::quote::Entity, _>(&txn, entity::quote::Column::Id)
+        .await
+        .map_err(save_error)?;
+    info!(id = quote_id, "Creating quote");
+
+    // Quotes created through the API don't come from the source sheet, so they
+    // are given a negative source ID that can't clash with a sheet's quote grouping.
+    let quote = entity::quote::ActiveModel {
+        id: Set(quote_id),
+        show_id: Set(location.show_id),
+        season_id: Set(location.season_id),
+        episode_id: Set(location.episode_id),
+        source_id: Set(-quote_id),
+    };
+    entity::quote::Entity::insert(quote)
+        .exec(&txn)
+        .await
+        .map_err(save_error)?;
+
+    create_quote_parts(&txn, quote_id, location.show_id, &request.parts)
+        .await
+        .map_err(save_error)?;
+
+    txn.commit().await.map_err(save_error)?;
+
+    return Ok(quote_id);
+}
+
+///
+/// Finds the show, season and episode that the quote is being saved against.
+///
+/// # Errors
+///
+/// When any of them don't exist, or the database returns an error.
+///
+pub(crate) async fn find_location<C>(
+    db: &C,
+    request: &SaveQuoteRequestBody,
+) -> Result<QuoteLocation, ErrorResult>
+where
+    C: ConnectionTrait,
+{
+    let location = find_quote_location(
+        db,
+        request.show_name.trim(),
+        request.season_no,
+        request.episode_no,
+    )
+    .await
+    .map_err(|e| db_error(e, "Error finding episode"))?;
+
+    return location.ok_or_else(|| {
+        ErrorResult::bad_request("Show, season or episode not found").with_field("episode_no")
+    });
+}
@@ -0,0 +1,111 @@
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use quoted_api_models::quote::{
+    GetDailyQuoteRequestParams, GetDailyQuoteResponse, GetRandomQuoteRequestParams,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use sha2::{Digest, Sha256};
+use tracing::{debug, info};
+
+use crate::{
+    api_response::{ApiResult, CachePolicy, ErrorResult, SuccessResult},
+    pipeline::{Context, Handler},
+};
+
+use super::{quote::find_quote, random_quote::pick_random_quote_ids};
+
+///
+/// Gets the quote of the day, optionally from a given show or character.
+///
+/// Everyone asking for the same day and filters gets the same quote. Nothing is
+/// stored; the quote is picked from the matching quotes using a seed derived
+/// from the date, so it only changes if the matching quotes change.
+///
+pub struct GetDailyQuote;
+
+impl Handler for GetDailyQuote {
+    type Params = GetDailyQuoteRequestParams;
+    type Response = GetDailyQuoteResponse;
+
+    async fn call(
+        params: GetDailyQuoteRequestParams,
+        ctx: &Context,
+    ) -> ApiResult<GetDailyQuoteResponse> {
+        let timezone = match params.timezone.as_deref() {
+            None => Tz::UTC,
+            Some(timezone) => timezone
+                .trim()
+                .parse::<Tz>()
+                .map_err(|_| ErrorResult::bad_request("Unknown timezone").with_field("timezone"))?,
+        };
+
+        let now = Utc::now();
+        let today = now.with_timezone(&timezone).date_naive();
+        debug!(%today, %timezone, "Picking quote of the day");
+
+        let filters = GetRandomQuoteRequestParams {
+            show_name: params.show_name,
+            character_name: params.character_name,
+            ..Default::default()
+        };
+        let mut rng = rng_for_date(today);
+
+        let quote_id = match pick_random_quote_ids(&ctx.db, &filters, 1, &mut rng)
+            .await?
+            .first()
+        {
+            None => {
+                info!("No quotes match found");
+                return Err(ErrorResult::not_found().with_message("Quote not found"));
+            }
+            Some(quote_id) => *quote_id,
+        };
+
+        let quote = match find_quote(&ctx.db, quote_id).await? {
+            None => return Err(ErrorResult::server_error("Error finding quote")),
+            Some(quote) => quote,
+        };
+
+        // The quote can be cached until the day ends in the requested timezone
+        let expires = start_of_day(today.succ_opt().unwrap_or(today), &timezone);
+        let max_age = (expires - now).num_seconds().max(0);
+
+        return Ok(SuccessResult::ok(quote)
+            .with_formats()
+            .with_cache(CachePolicy::Public {
+                max_age,
+                shared_max_age: max_age,
+            })
+            .with_last_modified(start_of_day(today, &timezone))
+            .with_header(
+                "Expires",
+                &expires.format("%a, %d %b %Y %H:%M:%S GMT").to_string(),
+            ));
+    }
+}
+
+/// Seeds the random selection from a hash of the date, so that it's the same
+/// on every machine, and the seeds of consecutive days aren't related.
+fn rng_for_date(date: NaiveDate) -> ChaCha8Rng {
+    let hash = Sha256::digest(date.format("%Y-%m-%d").to_string().as_bytes());
+    ChaCha8Rng::from_seed(hash.into())
+}
+
+/// Gets the start of `date` in the timezone, as UTC.
+fn start_of_day(date: NaiveDate, timezone: &Tz) -> DateTime<Utc> {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+
+    // Where the clocks go forward at midnight, the day starts an hour later
+    let start = timezone
+        .from_local_datetime(&midnight)
+        .earliest()
+        .or_else(|| {
+            timezone
+                .from_local_datetime(&(midnight + Duration::hours(1)))
+                .earliest()
+        })
+        .unwrap_or_else(|| timezone.from_utc_datetime(&midnight));
+
+    start.with_timezone(&Utc)
+}
@@ -0,0 +1,66 @@
+use crate::{
+    api_response::{ApiResult, ErrorResult, SuccessResult},
+    auth::require_role,
+    db_helper::delete_quote_parts,
+    pipeline::{db_error, Context, Handler},
+};
+use http::Method;
+use quoted_api_models::{
+    auth::Role,
+    quote::{DeleteQuoteResponse, GetQuoteRequestParams},
+};
+use quoted_db_entity as entity;
+use sea_orm::{DatabaseConnection, EntityTrait, TransactionTrait};
+use tracing::info;
+
+use super::quote::find_quote;
+
+///
+/// Deletes a quote along with its parts, returning the quote as it was.
+/// Characters are kept, even if they're no longer in any quotes.
+/// Requires the admin role.
+///
+pub struct DeleteQuote;
+
+impl Handler for DeleteQuote {
+    type Params = GetQuoteRequestParams;
+    type Response = DeleteQuoteResponse;
+
+    const METHOD: Method = Method::DELETE;
+
+    async fn call(params: GetQuoteRequestParams, ctx: &Context) -> ApiResult<DeleteQuoteResponse> {
+        require_role(ctx, Role::Admin).await?;
+
+        let quote = match find_quote(&ctx.db, params.id).await? {
+            None => {
+                info!(id = params.id, "Quote not found");
+                return Err(ErrorResult::not_found().with_message("Quote not found"));
+            }
+            Some(quote) => quote,
+        };
+
+        delete(&ctx.db, params.id).await?;
+
+        Ok(SuccessResult::ok(quote))
+    }
+}
+
+/// Deletes the parts of the quote and then the quote itself in a single transaction.
+async fn delete(db: &DatabaseConnection, quote_id: i32) -> Result<(), ErrorResult> {
+    let delete_error = |e| db_error(e, "Error deleting quote");
+
+    info!(id = quote_id, "Deleting quote");
+    let txn = db.begin().await.map_err(delete_error)?;
+
+    delete_quote_parts(&txn, quote_id)
+        .await
+        .map_err(delete_error)?;
+    entity::quote::Entity::delete_by_id(quote_id)
+        .exec(&txn)
+        .await
+        .map_err(delete_error)?;
+
+    txn.commit().await.map_err(delete_error)?;
+
+    return Ok(());
+}
@@ -0,0 +1,87 @@
+use crate::{
+    api_response::{ApiResult, CachePolicy, SuccessResult},
+    pipeline::{
+        fetch_all,
+        pagination::{count_total, decode_cursor, into_keyset_page, Paginate},
+        Context, Handler,
+    },
+};
+use quoted_api_models::episode::{
+    GetEpisodesInSeasonRequest, GetEpisodesInSeasonResponse, GetEpisodesInSeasonResponseItem,
+};
+use quoted_db_entity as entity;
+use sea_orm::{
+    prelude::Expr, sea_query::Alias, ColumnTrait, ConnectionTrait, DatabaseBackend, QueryFilter,
+    QueryOrder, Statement,
+};
+use sea_orm::{EntityTrait, QuerySelect, QueryTrait, Select};
+use tracing::debug;
+
+///
+/// Lists the episodes within a season of a show.
+///
+pub struct GetEpisodesInSeason;
+
+impl Handler for GetEpisodesInSeason {
+    type Params = GetEpisodesInSeasonRequest;
+    type Response = GetEpisodesInSeasonResponse;
+
+    async fn call(
+        params: GetEpisodesInSeasonRequest,
+        ctx: &Context,
+    ) -> ApiResult<GetEpisodesInSeasonResponse> {
+        let after = decode_cursor::<i32>(&params.cursor, params.page)?;
+        let stmt = build_query(&params, after, ctx.db.get_database_backend());
+        let episodes =
+            fetch_all::<GetEpisodesInSeasonResponseItem>(&ctx.db, stmt, "Error finding episodes")
+                .await?;
+        let total = count_total(&ctx.db, matching_episodes(&params), params.include_total).await?;
+
+        debug!("Returning result");
+        Ok(SuccessResult::ok(
+            into_keyset_page(params.page, params.limit, episodes, |episode| {
+                episode.episode_no
+            })
+            .with_total(total),
+        )
+        .with_cache(CachePolicy::CATALOGUE))
+    }
+}
+
+fn build_query(
+    request: &GetEpisodesInSeasonRequest,
+    after: Option<i32>,
+    db_backend: DatabaseBackend,
+) -> Statement {
+    let mut query = matching_episodes(request)
+        .select_only()
+        .column(entity::episode::Column::EpisodeNo)
+        .column_as(entity::episode::Column::Name, "episode_name")
+        .column_as(
+            Expr::col(entity::quote::Entity)
+                .count()
+                .cast_as(Alias::new("integer")),
+            "quote_count",
+        )
+        .left_join(entity::quote::Entity)
+        .paginate(request.page, request.limit)
+        .order_by_asc(entity::episode::Column::EpisodeNo)
+        .group_by(entity::episode::Column::EpisodeNo)
+        .group_by(entity::episode::Column::Name);
+
+    if let Some(episode_no) = after {
+        query = query.filter(entity::episode::Column::EpisodeNo.gt(episode_no));
+    }
+    let query = query.as_query().to_owned();
+
+    db_backend.build(&query)
+}
+
+/// Selects the episodes that match the filters in the request.
+fn matching_episodes(request: &GetEpisodesInSeasonRequest) -> Select<entity::episode::Entity> {
+    entity::episode::Entity::find()
+        .inner_join(entity::season::Entity)
+        .inner_join(entity::show::Entity)
+        .filter(entity::show::Column::Name.eq(&request.query.show))
+        .filter(entity::season::Column::SeasonNo.eq(request.query.season))
+}
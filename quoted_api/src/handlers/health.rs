@@ -0,0 +1,200 @@
+use std::{collections::HashSet, env};
+
+use quoted_api_models::health::{DatabaseHealth, GetHealthResponse, HealthStatus, MigrationHealth};
+use quoted_db_migration::{Migrator, MigratorTrait};
+use sea_orm::{
+    sea_query::{Alias, Query},
+    ConnectionTrait, DatabaseConnection, DbErr, FromQueryResult,
+};
+use tracing::{debug, warn};
+
+use crate::{
+    api_response::{ApiResult, SuccessResult},
+    pipeline::{rate_limit::RateLimit, Context, Handler, NoParams},
+};
+
+///
+/// The env var with the git commit that was deployed, which Vercel sets when
+/// system env vars are exposed to the deployment.
+///
+const COMMIT_ENV_VAR: &str = "VERCEL_GIT_COMMIT_SHA";
+
+///
+/// The error returned when the database can't be reached. The actual error is logged.
+///
+const DATABASE_UNREACHABLE: &str = "Database can't be reached";
+
+///
+/// Checks whether the API can serve requests, i.e. whether the database can be
+/// reached and has every migration in the deployment applied to it.
+///
+/// Returns `503 Service Unavailable` when it can't, so that uptime monitors only
+/// need to check the status code.
+///
+pub struct GetHealth;
+
+impl Handler for GetHealth {
+    type Params = NoParams;
+    type Response = GetHealthResponse;
+
+    // Monitors check the endpoint often, and shouldn't use up the rate limit table
+    const RATE_LIMIT: RateLimit = RateLimit::Unlimited;
+
+    // Reports that the database can't be reached when the pool can't be created
+    const REQUIRES_DB: bool = false;
+
+    async fn call(_params: NoParams, ctx: &Context) -> ApiResult<GetHealthResponse> {
+        let database = check_database(&ctx.db).await;
+        let migrations = match database.reachable {
+            false => None,
+            true => check_migrations(&ctx.db).await,
+        };
+
+        let healthy = database.reachable
+            && migrations
+                .as_ref()
+                .is_some_and(|migrations| migrations.pending.is_empty());
+        let status = match healthy {
+            true => HealthStatus::Ok,
+            false => HealthStatus::Unavailable,
+        };
+        debug!(?status, "Checked health");
+
+        let health = GetHealthResponse {
+            status,
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            commit: env::var(COMMIT_ENV_VAR)
+                .ok()
+                .filter(|commit| !commit.is_empty()),
+            database,
+            migrations,
+        };
+
+        let status_code = match healthy {
+            true => 200,
+            false => 503,
+        };
+
+        Ok(SuccessResult::ok(health).with_status(status_code))
+    }
+}
+
+///
+/// Pings the database. The error is only logged, as it can include details of the
+/// connection that shouldn't be returned from a public endpoint.
+///
+async fn check_database(db: &DatabaseConnection) -> DatabaseHealth {
+    return match quoted_db::ping(db).await {
+        Err(e) => {
+            warn!(error = ?e, "Database can't be reached");
+            DatabaseHealth {
+                reachable: false,
+                latency_ms: None,
+                error: Some(DATABASE_UNREACHABLE.to_owned()),
+            }
+        }
+        Ok(latency) => DatabaseHealth {
+            reachable: true,
+            latency_ms: Some(latency.as_millis() as u64),
+            error: None,
+        },
+    };
+}
+
+///
+/// Compares the migrations applied to the database with `Migrator::migrations()`,
+/// returning `None` when the applied migrations can't be read.
+///
+async fn check_migrations(db: &DatabaseConnection) -> Option<MigrationHealth> {
+    let applied = match applied_migrations(db).await {
+        Err(e) => {
+            warn!(error = ?e, "Error reading applied migrations");
+            return None;
+        }
+        Ok(applied) => applied,
+    };
+
+    let known = Migrator::migrations()
+        .iter()
+        .map(|migration| migration.name().to_owned())
+        .collect::<Vec<String>>();
+
+    let pending = known
+        .iter()
+        .filter(|name| !applied.contains(*name))
+        .cloned()
+        .collect::<Vec<String>>();
+    if !pending.is_empty() {
+        warn!(?pending, "Database has pending migrations");
+    }
+
+    let mut unknown = applied
+        .iter()
+        .filter(|name| !known.contains(name))
+        .cloned()
+        .collect::<Vec<String>>();
+    unknown.sort();
+
+    Some(MigrationHealth {
+        applied: applied.len(),
+        pending,
+        unknown,
+    })
+}
+
+#[derive(FromQueryResult)]
+struct AppliedMigration {
+    version: String,
+}
+
+///
+/// Reads the names of the migrations that have been applied to the database.
+///
+/// The migrations table is read directly rather than through `MigratorTrait`, which
+/// would create the table if it doesn't exist, as checking health shouldn't change
+/// the database. A database without the table reports an error, as nothing has
+/// been applied to it.
+///
+async fn applied_migrations(db: &DatabaseConnection) -> Result<HashSet<String>, DbErr> {
+    let query = Query::select()
+        .column(Alias::new("version"))
+        .from(Migrator::migration_table_name())
+        .to_owned();
+    let stmt = db.get_database_backend().build(&query);
+
+    let applied = AppliedMigration::find_by_statement(stmt).all(db).await?;
+
+    Ok(applied
+        .into_iter()
+        .map(|migration| migration.version)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::handle;
+    use vercel_runtime::Body;
+
+    #[tokio::test]
+    async fn health_is_unavailable_without_a_database() {
+        let req = http::Request::builder()
+            .uri("https://quoted.example/api/health")
+            .body(Body::Empty)
+            .unwrap();
+
+        let res = handle::<GetHealth>(req, DatabaseConnection::Disconnected)
+            .await
+            .unwrap();
+
+        assert_eq!(res.status(), 503);
+        let Body::Text(body) = res.body() else {
+            panic!("Expected a JSON body");
+        };
+        let health = serde_json::from_str::<GetHealthResponse>(body).unwrap();
+        assert_eq!(health.status, HealthStatus::Unavailable);
+        assert!(!health.database.reachable);
+        assert_eq!(health.database.error.as_deref(), Some(DATABASE_UNREACHABLE));
+        assert!(health.migrations.is_none());
+    }
+}
@@ -0,0 +1,58 @@
+use crate::{
+    api_response::{ApiResult, ErrorResult, SuccessResult},
+    auth::{issue_token, to_api_user, verify_dummy_password, verify_password},
+    pipeline::{db_error, rate_limit::RateLimit, Context, Handler, NoParams},
+};
+use http::Method;
+use quoted_api_models::auth::{LoginRequest, LoginResponse};
+use quoted_db_entity as entity;
+use sea_orm::{entity::*, EntityTrait, QueryFilter};
+use tracing::warn;
+
+///
+/// Logs a user in, issuing a token that can be used to call protected endpoints.
+///
+pub struct Login;
+
+impl Handler for Login {
+    type Params = NoParams;
+    type Response = LoginResponse;
+
+    const METHOD: Method = Method::POST;
+    const RATE_LIMIT: RateLimit = RateLimit::LOGIN;
+
+    async fn call(_params: NoParams, ctx: &Context) -> ApiResult<LoginResponse> {
+        let request = ctx.json::<LoginRequest>()?;
+
+        let user = entity::users::Entity::find()
+            .filter(entity::users::Column::Email.eq(request.email.trim()))
+            .one(&ctx.db)
+            .await
+            .map_err(|e| db_error(e, "Error logging in"))?;
+
+        // The same error is returned whether or not the user exists, and a password is
+        // checked either way so that both take as long, so that the endpoint can't be
+        // used to find out who has an account.
+        let verified = match &user {
+            None => verify_dummy_password(&request.password),
+            Some(user) => verify_password(&request.password, &user.password),
+        };
+        let user = match user {
+            Some(user) if verified => user,
+            _ => {
+                warn!(email = %request.email, "Failed login");
+                return Err(ErrorResult::unauthorized("Invalid email or password"));
+            }
+        };
+
+        let (token, expires_at) = issue_token(&ctx.db, user.id)
+            .await
+            .map_err(|e| db_error(e, "Error logging in"))?;
+
+        Ok(SuccessResult::ok(LoginResponse {
+            token,
+            expires_at: expires_at.to_rfc3339(),
+            user: to_api_user(user)?,
+        }))
+    }
+}
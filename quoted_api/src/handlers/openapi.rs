@@ -0,0 +1,22 @@
+use utoipa::openapi::OpenApi;
+
+use crate::{
+    api_response::{ApiResult, CachePolicy, SuccessResult},
+    openapi::document,
+    pipeline::{Context, Handler, NoParams},
+};
+
+///
+/// Gets the OpenAPI document describing every endpoint, which can be used to
+/// generate clients for the API.
+///
+pub struct GetOpenApi;
+
+impl Handler for GetOpenApi {
+    type Params = NoParams;
+    type Response = OpenApi;
+
+    async fn call(_params: NoParams, _ctx: &Context) -> ApiResult<OpenApi> {
+        Ok(SuccessResult::ok(document()).with_cache(CachePolicy::CATALOGUE))
+    }
+}
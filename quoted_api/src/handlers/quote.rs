@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use crate::{
+    api_response::{ApiResult, CachePolicy, ErrorResult, SuccessResult},
+    models::quote_models::{select_quote_columns, QuoteDBResult},
+    pipeline::{fetch_all, Context, Handler},
+};
+use quoted_api_models::quote::{GetQuoteRequest, GetQuoteResponse};
+use quoted_db_entity as entity;
+use sea_orm::ConnectionTrait;
+use sea_orm::{
+    sea_query::{Expr, PgFunc},
+    DatabaseBackend, DatabaseConnection, EntityTrait, QueryFilter, QueryTrait, Statement,
+};
+use tracing::{debug, info};
+
+///
+/// Gets a single quote by its ID.
+///
+pub struct GetQuote;
+
+impl Handler for GetQuote {
+    type Params = GetQuoteRequest;
+    type Response = GetQuoteResponse;
+
+    async fn call(params: GetQuoteRequest, ctx: &Context) -> ApiResult<GetQuoteResponse> {
+        return match find_quote(&ctx.db, params.id).await? {
+            None => {
+                info!(id = params.id, "Quote not found");
+                Err(ErrorResult::not_found().with_message("Quote not found"))
+            }
+            Some(quote) => Ok(SuccessResult::ok(quote)
+                .with_cache(CachePolicy::QUOTES)
+                .with_formats()),
+        };
+    }
+}
+
+///
+/// Finds the quote with the given ID, along with its parts.
+///
+pub(crate) async fn find_quote(
+    db: &DatabaseConnection,
+    quote_id: i32,
+) -> Result<Option<GetQuoteResponse>, ErrorResult> {
+    Ok(find_quotes(db, &[quote_id]).await?.pop())
+}
+
+///
+/// Finds the quotes with the given IDs, along with their parts.
+/// The quotes are returned in the same order as the IDs, and any that don't
+/// exist are left out.
+///
+pub(crate) async fn find_quotes(
+    db: &DatabaseConnection,
+    quote_ids: &[i32],
+) -> Result<Vec<GetQuoteResponse>, ErrorResult> {
+    debug!("Executing quote query");
+    let query = build_quote_query(quote_ids, db.get_database_backend());
+    let quotes = fetch_all::<QuoteDBResult>(db, query, "Error finding quote").await?;
+
+    let mut quotes = quotes
+        .iter()
+        .map(|q| Ok((q.quote_id, q.to_api_response()?)))
+        .collect::<Result<HashMap<i32, GetQuoteResponse>, ErrorResult>>()?;
+
+    Ok(quote_ids
+        .iter()
+        .filter_map(|id| quotes.remove(id))
+        .collect())
+}
+
+fn build_quote_query(quote_ids: &[i32], db_backend: DatabaseBackend) -> Statement {
+    let query = entity::quote::Entity::find()
+        .inner_join(entity::episode::Entity)
+        .inner_join(entity::season::Entity)
+        .inner_join(entity::show::Entity)
+        .filter(Expr::eq(
+            Expr::col((entity::quote::Entity, entity::quote::Column::Id)),
+            Expr::expr(PgFunc::any(quote_ids.to_vec())),
+        ));
+    let query = select_quote_columns(query).as_query().to_owned();
+
+    db_backend.build(&query)
+}
@@ -0,0 +1,169 @@
+use crate::{
+    api_response::{ApiResult, CachePolicy, ErrorResult, SuccessResult},
+    models::quote_models::{select_quote_columns, QuoteDBResult},
+    pipeline::{
+        fetch_all,
+        pagination::{count_total, decode_cursor, into_keyset_page, into_page, Paginate},
+        Context, Handler,
+    },
+};
+use prelude::Expr;
+use quoted_api_models::{
+    page::SortDirection,
+    quote::{GetQuotesRequest, GetQuotesResponse, GetQuotesResponseItem, QuoteSort},
+};
+use quoted_db_entity as entity;
+use sea_orm::ConnectionTrait;
+use sea_orm::{
+    entity::*, sea_query::SimpleExpr, DatabaseBackend, EntityTrait, Order, QueryFilter, QueryOrder,
+    QueryTrait, Select, Statement,
+};
+
+///
+/// The sort keys of a quote when sorted chronologically; the show name, season number,
+/// episode number and quote ID. Cursors are only supported by the chronological sort.
+///
+type QuoteCursor = (String, i32, i32, i32);
+
+///
+/// Lists the quotes within a show, season or episode.
+///
+pub struct GetQuotes;
+
+impl Handler for GetQuotes {
+    type Params = GetQuotesRequest;
+    type Response = GetQuotesResponse;
+
+    async fn call(params: GetQuotesRequest, ctx: &Context) -> ApiResult<GetQuotesResponse> {
+        let db_backend = ctx.db.get_database_backend();
+        let after = decode_cursor::<QuoteCursor>(&params.cursor, params.page)?;
+
+        let query = build_quote_query(&params, after, &db_backend);
+        let quotes = fetch_all::<QuoteDBResult>(&ctx.db, query, "Error fetching quotes")
+            .await?
+            .iter()
+            .map(QuoteDBResult::to_api_response)
+            .collect::<Result<Vec<GetQuotesResponseItem>, ErrorResult>>()?;
+
+        let page = match params.sort {
+            QuoteSort::Chronological => {
+                into_keyset_page(params.page, params.limit, quotes, |quote| {
+                    (
+                        quote.show_name.clone(),
+                        quote.season_no,
+                        quote.episode_no,
+                        quote.id,
+                    )
+                })
+            }
+            _ => into_page(params.page, params.limit, quotes),
+        };
+
+        let total = count_total(&ctx.db, matching_quotes(&params), params.include_total).await?;
+
+        Ok(SuccessResult::ok(page.with_total(total))
+            .with_cache(CachePolicy::QUOTES)
+            .with_formats())
+    }
+}
+
+fn build_quote_query(
+    query_params: &GetQuotesRequest,
+    after: Option<QuoteCursor>,
+    db_backend: &DatabaseBackend,
+) -> Statement {
+    let mut query = matching_quotes(query_params);
+    let sort_keys = sort_keys(query_params);
+
+    if let Some((show_name, season_no, episode_no, quote_id)) = after {
+        let keys = Expr::tuple(sort_keys.clone());
+        let values = Expr::tuple([
+            show_name.into(),
+            season_no.into(),
+            episode_no.into(),
+            quote_id.into(),
+        ]);
+        query = query.filter(match query_params.direction {
+            SortDirection::Asc => keys.gt(values),
+            SortDirection::Desc => keys.lt(values),
+        });
+    }
+
+    let order = match query_params.direction {
+        SortDirection::Asc => Order::Asc,
+        SortDirection::Desc => Order::Desc,
+    };
+    for key in sort_keys {
+        query = query.order_by(key, order.clone());
+    }
+
+    // Add the columns to be selected, including the parts of each quote
+    let query = select_quote_columns(query)
+        .paginate(query_params.page, query_params.limit)
+        .as_query()
+        .to_owned();
+
+    // build the query
+    db_backend.build(&query)
+}
+
+///
+/// Gets the expressions that the quotes are ordered by for the requested sort.
+/// The quote ID is always the last key, so that the order is stable.
+///
+fn sort_keys(query_params: &GetQuotesRequest) -> Vec<SimpleExpr> {
+    let quote_id = Expr::col((entity::quote::Entity, entity::quote::Column::Id)).into();
+
+    return match query_params.sort {
+        QuoteSort::Chronological => vec![
+            Expr::col((entity::show::Entity, entity::show::Column::Name)).into(),
+            Expr::col((entity::season::Entity, entity::season::Column::SeasonNo)).into(),
+            Expr::col((entity::episode::Entity, entity::episode::Column::EpisodeNo)).into(),
+            quote_id,
+        ],
+        QuoteSort::Character => vec![
+            Expr::cust(
+                r#"(select "character"."name" from "quote_part" inner join "character" on "character"."id" = "quote_part"."character_id" where "quote_part"."quote_id" = "quote"."id" order by "quote_part"."order_no" limit 1)"#,
+            ),
+            quote_id,
+        ],
+        QuoteSort::Length => vec![
+            Expr::cust(
+                r#"(select sum(length("quote_part"."value")) from "quote_part" where "quote_part"."quote_id" = "quote"."id")"#,
+            ),
+            quote_id,
+        ],
+        // Hashing the ID with the seed gives an order that's random, but the same
+        // for every page requested with the seed.
+        QuoteSort::Random => vec![
+            Expr::cust_with_values(
+                r#"md5("quote"."id"::text || $1)"#,
+                [query_params.seed.unwrap_or_default().to_string()],
+            ),
+            quote_id,
+        ],
+    };
+}
+
+/// Selects the quotes that match the filters in the request, joining the
+/// tables that are needed by both the filters and the columns selected.
+fn matching_quotes(query_params: &GetQuotesRequest) -> Select<entity::quote::Entity> {
+    // Start by wiring up the required joins
+    let mut query = entity::quote::Entity::find()
+        .inner_join(entity::episode::Entity)
+        .inner_join(entity::season::Entity)
+        .inner_join(entity::show::Entity);
+
+    // Conditionally apply any filters based on query params
+    if let Some(show_name) = &query_params.show_name {
+        query = query.filter(entity::show::Column::Name.eq(show_name));
+    }
+    if let Some(season_no) = &query_params.season_no {
+        query = query.filter(entity::season::Column::SeasonNo.eq(*season_no));
+    }
+    if let Some(episode_no) = &query_params.episode_no {
+        query = query.filter(entity::episode::Column::EpisodeNo.eq(*episode_no));
+    }
+
+    query
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+
+use crate::{
+    api_response::{ApiResult, CachePolicy, ErrorResult, SuccessResult},
+    pipeline::{db_error, rate_limit::RateLimit, Context, Handler},
+};
+use quoted_api_models::quote::{GetRandomQuoteRequestParams, GetRandomQuoteResponse};
+use quoted_db_entity::{self as entity};
+use rand::{seq::index::sample, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use sea_orm::{
+    entity::*,
+    sea_query::{Alias, Expr, PgFunc, Query},
+    ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect, QueryTrait, Select,
+};
+use tracing::{debug, info};
+
+use super::quote::find_quotes;
+
+///
+/// Gets random quotes, optionally from a given show, season, episode or character.
+///
+pub struct GetRandomQuote;
+
+impl Handler for GetRandomQuote {
+    type Params = GetRandomQuoteRequestParams;
+    type Response = GetRandomQuoteResponse;
+
+    const RATE_LIMIT: RateLimit = RateLimit::EXPENSIVE;
+
+    async fn call(
+        params: GetRandomQuoteRequestParams,
+        ctx: &Context,
+    ) -> ApiResult<GetRandomQuoteResponse> {
+        let count = params.count.unwrap_or(1);
+
+        // ChaCha is used rather than StdRng, as its output for a given seed is
+        // guaranteed not to change between versions of rand.
+        let mut rng = match params.seed {
+            Some(seed) => ChaCha8Rng::seed_from_u64(seed),
+            None => ChaCha8Rng::from_entropy(),
+        };
+
+        debug!(count, "Picking random quotes");
+        let quote_ids = pick_random_quote_ids(&ctx.db, &params, count as usize, &mut rng).await?;
+        if quote_ids.is_empty() {
+            info!("No quotes match found");
+            return Err(ErrorResult::not_found().with_message("Quote not found"));
+        }
+
+        let quotes = find_quotes(&ctx.db, &quote_ids).await?;
+
+        // Seeded quotes are always the same, so they can be cached like any other
+        // quotes, but without a seed each request should get new quotes
+        let cache = match params.seed {
+            Some(_) => CachePolicy::QUOTES,
+            None => CachePolicy::NoStore,
+        };
+
+        Ok(SuccessResult::ok(quotes).with_cache(cache).with_formats())
+    }
+}
+
+///
+/// Picks up to `count` different random quotes that match the filters, returning their IDs.
+///
+/// Ordering every matching row by `random()` means sorting the whole data set on
+/// every request. Instead, the matching quotes are counted, and the quotes are
+/// picked by their position when ordered by ID. Neither query needs a sort, as the
+/// rows are read in primary key order, and only the tables needed by the filters
+/// are joined. The number of queries is the same regardless of `count`.
+///
+pub async fn pick_random_quote_ids<C, R>(
+    db: &C,
+    params: &GetRandomQuoteRequestParams,
+    count: usize,
+    rng: &mut R,
+) -> Result<Vec<i32>, ErrorResult>
+where
+    C: ConnectionTrait,
+    R: Rng,
+{
+    let total = matching_quotes(params)
+        .select_only()
+        .column_as(
+            Expr::col((entity::quote::Entity, entity::quote::Column::Id)).count(),
+            "count",
+        )
+        .into_tuple::<i64>()
+        .one(db)
+        .await
+        .map_err(|e| db_error(e, "Error finding random quote"))?
+        .unwrap_or_default() as usize;
+
+    if total == 0 {
+        return Ok(vec![]);
+    }
+
+    let positions = sample(rng, total, count.min(total))
+        .into_iter()
+        .map(|p| p as i64)
+        .collect::<Vec<i64>>();
+    debug!(?positions, total, "Picking quotes");
+
+    // Only the rows up to the last position picked need to be read
+    let last_position = positions.iter().max().copied().unwrap_or_default();
+    let numbered_quotes = matching_quotes(params)
+        .select_only()
+        .column_as(entity::quote::Column::Id, "id")
+        .expr_as(
+            Expr::cust(r#"row_number() over (order by "quote"."id") - 1"#),
+            "position",
+        )
+        .order_by_asc(entity::quote::Column::Id)
+        .limit(last_position as u64 + 1)
+        .into_query();
+
+    let query = Query::select()
+        .column(Alias::new("id"))
+        .column(Alias::new("position"))
+        .from_subquery(numbered_quotes, Alias::new("numbered_quote"))
+        .and_where(Expr::col(Alias::new("position")).eq(PgFunc::any(positions.clone())))
+        .to_owned();
+
+    let picked = db
+        .query_all(db.get_database_backend().build(&query))
+        .await
+        .map_err(|e| db_error(e, "Error finding random quote"))?
+        .iter()
+        .map(|row| {
+            Ok((
+                row.try_get::<i64>("", "position")?,
+                row.try_get::<i32>("", "id")?,
+            ))
+        })
+        .collect::<Result<HashMap<i64, i32>, DbErr>>()
+        .map_err(|e| db_error(e, "Error finding random quote"))?;
+
+    // Keep the quotes in the order they were picked, so the order is random too
+    Ok(positions
+        .iter()
+        .filter_map(|position| picked.get(position).copied())
+        .collect())
+}
+
+/// Selects the quotes that match the filters, joining only the tables needed to apply them.
+fn matching_quotes(params: &GetRandomQuoteRequestParams) -> Select<entity::quote::Entity> {
+    let mut query = entity::quote::Entity::find();
+
+    if let Some(show_name) = &params.show_name {
+        query = query
+            .inner_join(entity::show::Entity)
+            .filter(entity::show::Column::Name.eq(show_name));
+    }
+    if let Some(season_no) = &params.season_no {
+        query = query
+            .inner_join(entity::season::Entity)
+            .filter(entity::season::Column::SeasonNo.eq(*season_no));
+    }
+    if let Some(episode_no) = &params.episode_no {
+        query = query
+            .inner_join(entity::episode::Entity)
+            .filter(entity::episode::Column::EpisodeNo.eq(*episode_no));
+    }
+    if let Some(character_name) = &params.character_name {
+        // A character can have many parts in a quote, so a sub query is
+        // used rather than a join to avoid the quote being counted more than once.
+        let quotes_with_character = Query::select()
+            .column(entity::quote_part::Column::QuoteId)
+            .from(entity::quote_part::Entity)
+            .inner_join(
+                entity::character::Entity,
+                Expr::col((entity::character::Entity, entity::character::Column::Id)).equals((
+                    entity::quote_part::Entity,
+                    entity::quote_part::Column::CharacterId,
+                )),
+            )
+            .and_where(
+                Expr::col((entity::character::Entity, entity::character::Column::Name))
+                    .eq(character_name),
+            )
+            .to_owned();
+
+        query = query.filter(entity::quote::Column::Id.in_subquery(quotes_with_character));
+    }
+
+    query
+}
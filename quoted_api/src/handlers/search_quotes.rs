@@ -0,0 +1,182 @@
+use crate::{
+    api_response::{ApiResult, CachePolicy, ErrorResult, SuccessResult},
+    models::quote_models::{select_quote_columns, QuoteSearchDBResult},
+    pipeline::{
+        fetch_all,
+        pagination::{into_page, Paginate},
+        rate_limit::RateLimit,
+        Context, Handler,
+    },
+};
+use prelude::Expr;
+use quoted_api_models::quote::{
+    SearchQuotesRequest, SearchQuotesResponse, SearchQuotesResponseItem,
+};
+use quoted_db_entity as entity;
+use sea_orm::ConnectionTrait;
+use sea_orm::{
+    entity::*,
+    sea_query::{Alias, Query, SimpleExpr},
+    DatabaseBackend, EntityTrait, Order, QueryFilter, QueryOrder, QuerySelect, QueryTrait,
+    Statement,
+};
+
+///
+/// The text search configuration used when building `quote_part.search_vector`.
+/// The search query must be parsed with the same configuration for matches to be found.
+///
+const SEARCH_CONFIG: &str = "english";
+
+///
+/// Searches the text of quotes, returning the best matches first.
+///
+pub struct SearchQuotes;
+
+impl Handler for SearchQuotes {
+    type Params = SearchQuotesRequest;
+    type Response = SearchQuotesResponse;
+
+    const RATE_LIMIT: RateLimit = RateLimit::EXPENSIVE;
+
+    async fn call(params: SearchQuotesRequest, ctx: &Context) -> ApiResult<SearchQuotesResponse> {
+        let db_backend = ctx.db.get_database_backend();
+
+        let query = build_search_query(&params, &db_backend);
+        let quotes = fetch_all::<QuoteSearchDBResult>(&ctx.db, query, "Error searching quotes")
+            .await?
+            .iter()
+            .map(QuoteSearchDBResult::to_api_response)
+            .collect::<Result<Vec<SearchQuotesResponseItem>, ErrorResult>>()?;
+
+        Ok(
+            SuccessResult::ok(into_page(params.page, params.limit, quotes))
+                .with_cache(CachePolicy::QUOTES)
+                .with_formats(),
+        )
+    }
+}
+
+/// Builds an expression that parses the search text into a `tsquery`
+/// using `SEARCH_CONFIG`.
+fn ts_query(search: &str) -> SimpleExpr {
+    Expr::cust_with_values(
+        format!("websearch_to_tsquery('{}', $1)", SEARCH_CONFIG),
+        [search],
+    )
+}
+
+/// Builds an expression that escapes the HTML special characters in the text of `column`.
+/// `&` is escaped first, so that the other escapes aren't escaped again.
+fn escape_html(column: &str) -> String {
+    [
+        ("&", "&amp;"),
+        ("<", "&lt;"),
+        (">", "&gt;"),
+        ("\"", "&quot;"),
+    ]
+    .iter()
+    .fold(column.to_owned(), |expr, (from, to)| {
+        format!("replace({expr}, '{from}', '{to}')")
+    })
+}
+
+fn build_search_query(
+    query_params: &SearchQuotesRequest,
+    db_backend: &DatabaseBackend,
+) -> Statement {
+    // Only quotes with at least one matching part are included.
+    // Matching happens in a sub query so that the GIN index on search_vector is used.
+    let matching_quote_ids = Query::select()
+        .column(entity::quote_part::Column::QuoteId)
+        .from(entity::quote_part::Entity)
+        .and_where(Expr::cust_with_expr(
+            "search_vector @@ $1",
+            ts_query(&query_params.q),
+        ))
+        .to_owned();
+
+    // Start by wiring up the required joins
+    let mut query = entity::quote::Entity::find()
+        .inner_join(entity::episode::Entity)
+        .inner_join(entity::season::Entity)
+        .inner_join(entity::show::Entity)
+        .inner_join(entity::quote_part::Entity)
+        .filter(entity::quote::Column::Id.in_subquery(matching_quote_ids));
+
+    // Conditionally apply any filters based on query params
+    if let Some(show_name) = &query_params.show_name {
+        query = query.filter(entity::show::Column::Name.eq(show_name));
+    }
+    if let Some(season_no) = &query_params.season_no {
+        query = query.filter(entity::season::Column::SeasonNo.eq(*season_no));
+    }
+    if let Some(episode_no) = &query_params.episode_no {
+        query = query.filter(entity::episode::Column::EpisodeNo.eq(*episode_no));
+    }
+
+    // Add the columns to be selected, including the parts of each quote.
+    // The rank of a quote is the rank of its best matching part, and the snippet
+    // is taken from the text of all parts, in order. The text is escaped before the
+    // matches are marked, so that the snippet can be rendered as HTML.
+    let query = select_quote_columns(query)
+        .expr_as(
+            Expr::cust_with_expr(
+                r#"max(ts_rank("quote_part"."search_vector", $1))"#,
+                ts_query(&query_params.q),
+            ),
+            "rank",
+        )
+        .expr_as(
+            Expr::cust_with_expr(
+                format!(
+                    r#"ts_headline('{}', string_agg({}, ' ' order by "quote_part"."order_no"), $1, 'StartSel=<mark>, StopSel=</mark>')"#,
+                    SEARCH_CONFIG,
+                    escape_html(r#""quote_part"."value""#)
+                ),
+                ts_query(&query_params.q),
+            ),
+            "snippet",
+        )
+        .group_by(entity::quote::Column::Id)
+        .group_by(entity::show::Column::Name)
+        .group_by(entity::season::Column::SeasonNo)
+        .group_by(entity::season::Column::Name)
+        .group_by(entity::episode::Column::EpisodeNo)
+        .group_by(entity::episode::Column::Name)
+        .order_by(Expr::col(Alias::new("rank")), Order::Desc)
+        .order_by_asc(entity::quote::Column::Id)
+        .paginate(query_params.page, query_params.limit)
+        .as_query()
+        .to_owned();
+
+    // build the query
+    db_backend.build(&query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn html_is_escaped_starting_with_ampersands() {
+        assert_eq!(
+            escape_html("value"),
+            r#"replace(replace(replace(replace(value, '&', '&amp;'), '<', '&lt;'), '>', '&gt;'), '"', '&quot;')"#
+        );
+    }
+
+    #[test]
+    fn snippets_are_taken_from_the_escaped_text() {
+        let params = SearchQuotesRequest {
+            q: "shut up".to_owned(),
+            ..Default::default()
+        };
+
+        let stmt = build_search_query(&params, &DatabaseBackend::Postgres);
+
+        assert!(stmt.sql.contains(&format!(
+            r#"ts_headline('english', string_agg({}, ' ' order by "quote_part"."order_no")"#,
+            escape_html(r#""quote_part"."value""#)
+        )));
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{
+    api_response::{ApiResult, CachePolicy, SuccessResult},
+    pipeline::{
+        fetch_all,
+        pagination::{count_total, decode_cursor, into_keyset_page, Paginate},
+        Context, Handler,
+    },
+};
+use quoted_api_models::season::{
+    GetSeasonsInShowRequest, GetSeasonsInShowResponse, GetSeasonsInShowResponseItem,
+};
+use quoted_db_entity as entity;
+use sea_orm::{
+    prelude::Expr, sea_query::Alias, ColumnTrait, ConnectionTrait, DatabaseBackend, QueryFilter,
+    Statement,
+};
+use sea_orm::{EntityTrait, QueryOrder, QuerySelect, QueryTrait, Select};
+use tracing::debug;
+
+///
+/// Lists the seasons within a show.
+///
+pub struct GetSeasonsInShow;
+
+impl Handler for GetSeasonsInShow {
+    type Params = GetSeasonsInShowRequest;
+    type Response = GetSeasonsInShowResponse;
+
+    async fn call(
+        params: GetSeasonsInShowRequest,
+        ctx: &Context,
+    ) -> ApiResult<GetSeasonsInShowResponse> {
+        let after = decode_cursor::<i32>(&params.cursor, params.page)?;
+        let stmt = build_query(&params, after, ctx.db.get_database_backend());
+        let seasons =
+            fetch_all::<GetSeasonsInShowResponseItem>(&ctx.db, stmt, "Error finding seasons")
+                .await?;
+        let total = count_total(&ctx.db, matching_seasons(&params), params.include_total).await?;
+
+        debug!("Returning result");
+        Ok(SuccessResult::ok(
+            into_keyset_page(params.page, params.limit, seasons, |season| {
+                season.season_no
+            })
+            .with_total(total),
+        )
+        .with_cache(CachePolicy::CATALOGUE))
+    }
+}
+
+fn build_query(
+    request: &GetSeasonsInShowRequest,
+    after: Option<i32>,
+    db_backend: DatabaseBackend,
+) -> Statement {
+    let mut query = matching_seasons(request)
+        .select_only()
+        .column(entity::season::Column::SeasonNo)
+        .column_as(entity::season::Column::Name, "season_name")
+        .column_as(
+            Expr::col(entity::quote::Entity)
+                .count()
+                .cast_as(Alias::new("integer")),
+            "quote_count",
+        )
+        .left_join(entity::quote::Entity)
+        .group_by(entity::season::Column::SeasonNo)
+        .group_by(entity::season::Column::Name)
+        .order_by_asc(entity::season::Column::SeasonNo)
+        .paginate(request.page, request.limit);
+
+    if let Some(season_no) = after {
+        query = query.filter(entity::season::Column::SeasonNo.gt(season_no));
+    }
+    let query = query.as_query().to_owned();
+
+    db_backend.build(&query)
+}
+
+/// Selects the seasons that match the filters in the request.
+fn matching_seasons(request: &GetSeasonsInShowRequest) -> Select<entity::season::Entity> {
+    entity::season::Entity::find()
+        .inner_join(entity::show::Entity)
+        .filter(entity::show::Column::Name.eq(&request.query.show))
+}
@@ -0,0 +1,81 @@
+use crate::{
+    api_response::{ApiResult, CachePolicy, SuccessResult},
+    pipeline::{
+        fetch_all,
+        pagination::{count_total, decode_cursor, into_keyset_page, Paginate},
+        Context, Handler,
+    },
+};
+use quoted_api_models::show::{GetShowsRequest, GetShowsResponse, GetShowsResponseItem};
+use quoted_db_entity as entity;
+use sea_orm::{
+    prelude::Expr,
+    sea_query::{extension::postgres::PgExpr, Alias},
+    DatabaseBackend, EntityTrait, QueryOrder, QuerySelect, QueryTrait, Select, Statement,
+};
+use sea_orm::{ColumnTrait, ConnectionTrait, QueryFilter};
+use tracing::debug;
+
+///
+/// Lists the available shows.
+///
+pub struct GetShows;
+
+impl Handler for GetShows {
+    type Params = GetShowsRequest;
+    type Response = GetShowsResponse;
+
+    async fn call(params: GetShowsRequest, ctx: &Context) -> ApiResult<GetShowsResponse> {
+        let after = decode_cursor::<String>(&params.cursor, params.page)?;
+
+        debug!("Building query");
+        let stmt = build_query(&params, after, ctx.db.get_database_backend());
+        let shows = fetch_all::<GetShowsResponseItem>(&ctx.db, stmt, "Error finding shows").await?;
+        let total = count_total(&ctx.db, matching_shows(&params), params.include_total).await?;
+
+        debug!("Returning result");
+        Ok(SuccessResult::ok(
+            into_keyset_page(params.page, params.limit, shows, |show| show.name.clone())
+                .with_total(total),
+        )
+        .with_cache(CachePolicy::CATALOGUE))
+    }
+}
+
+fn build_query(
+    request: &GetShowsRequest,
+    after: Option<String>,
+    db_backend: DatabaseBackend,
+) -> Statement {
+    let mut query = matching_shows(request)
+        .select_only()
+        .column(entity::show::Column::Name)
+        .column_as(
+            Expr::col(entity::quote::Entity)
+                .count()
+                .cast_as(Alias::new("integer")),
+            "quote_count",
+        )
+        .left_join(entity::quote::Entity)
+        .group_by(entity::show::Column::Name)
+        .order_by_asc(entity::show::Column::Name)
+        .paginate(request.page, request.limit);
+
+    if let Some(name) = after {
+        query = query.filter(entity::show::Column::Name.gt(name));
+    }
+    let query = query.as_query().to_owned();
+
+    db_backend.build(&query)
+}
+
+/// Selects the shows that match the filters in the request.
+fn matching_shows(request: &GetShowsRequest) -> Select<entity::show::Entity> {
+    let mut query = entity::show::Entity::find();
+
+    if let Some(name) = &request.query.name {
+        query = query.filter(Expr::col(entity::show::Column::Name).ilike(format!("%{}%", name)));
+    }
+
+    query
+}
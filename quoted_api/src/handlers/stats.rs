@@ -0,0 +1,289 @@
+use quoted_api_models::stats::{
+    CharacterStats, EpisodeStats, GetStatsRequest, GetStatsResponse, StatsTotals, DEFAULT_STATS_TOP,
+};
+use quoted_db_entity as entity;
+use sea_orm::{
+    prelude::Expr,
+    sea_query::{Alias, Asterisk, Order, Query, SelectStatement, SimpleExpr},
+    ColumnTrait, ConnectionTrait, DatabaseBackend, EntityTrait, JoinType, QueryFilter, QueryOrder,
+    QuerySelect, QueryTrait, Statement,
+};
+use tracing::{debug, info};
+
+use crate::{
+    api_response::{ApiResult, CachePolicy, ErrorResult, SuccessResult},
+    db_helper::quote_in_show,
+    pipeline::{db_error, fetch_all, fetch_one, Context, Handler},
+};
+
+///
+/// Gets statistics about the quotes in the catalogue, optionally limited to a single show.
+///
+/// The totals and rankings are counted on every request, which is fast enough for the
+/// size of the catalogue, and the response is cached like the rest of the catalogue.
+///
+pub struct GetStats;
+
+impl Handler for GetStats {
+    type Params = GetStatsRequest;
+    type Response = GetStatsResponse;
+
+    async fn call(params: GetStatsRequest, ctx: &Context) -> ApiResult<GetStatsResponse> {
+        let show_id = match &params.show_name {
+            None => None,
+            Some(show_name) => Some(find_show_id(ctx, show_name.trim()).await?),
+        };
+        let top = params.top.unwrap_or(DEFAULT_STATS_TOP) as u64;
+        let db_backend = ctx.db.get_database_backend();
+
+        debug!(?show_id, top, "Counting stats");
+        let totals = fetch_one::<StatsTotals>(
+            &ctx.db,
+            build_totals_query(show_id, db_backend),
+            "Error counting totals",
+        )
+        .await?
+        .ok_or_else(|| ErrorResult::server_error("Error counting totals"))?;
+
+        let top_characters = fetch_all::<CharacterStats>(
+            &ctx.db,
+            build_top_characters_query(show_id, top, db_backend),
+            "Error finding top characters",
+        )
+        .await?;
+
+        let top_episodes = fetch_all::<EpisodeStats>(
+            &ctx.db,
+            build_top_episodes_query(show_id, top, db_backend),
+            "Error finding top episodes",
+        )
+        .await?;
+
+        let average_parts_per_quote = match totals.quotes {
+            0 => 0.0,
+            quotes => totals.quote_parts as f64 / quotes as f64,
+        };
+
+        Ok(SuccessResult::ok(GetStatsResponse {
+            totals,
+            average_parts_per_quote,
+            top_characters,
+            top_episodes,
+        })
+        .with_cache(CachePolicy::CATALOGUE))
+    }
+}
+
+///
+/// Finds the ID of the show with the given name.
+///
+/// # Errors
+///
+/// When the show doesn't exist, or the database returns an error.
+///
+async fn find_show_id(ctx: &Context, show_name: &str) -> Result<i32, ErrorResult> {
+    let show = entity::show::Entity::find()
+        .filter(entity::show::Column::Name.eq(show_name))
+        .one(&ctx.db)
+        .await
+        .map_err(|e| db_error(e, "Error finding show"))?;
+
+    return match show {
+        None => {
+            info!(show_name, "Show not found");
+            Err(ErrorResult::bad_request("Show not found").with_field("show_name"))
+        }
+        Some(show) => Ok(show.id),
+    };
+}
+
+///
+/// Counts every kind of item in a single statement, with a subquery for each.
+///
+fn build_totals_query(show_id: Option<i32>, db_backend: DatabaseBackend) -> Statement {
+    let parts_query = entity::quote_part::Entity::find()
+        .select_only()
+        .expr(Expr::col(Asterisk).count())
+        .inner_join(entity::quote::Entity)
+        .apply_if(show_id, |query, show_id| {
+            query.filter(entity::quote::Column::ShowId.eq(show_id))
+        })
+        .into_query();
+
+    let query = Query::select()
+        .expr_as(
+            count_query(entity::show::Entity, entity::show::Column::Id, show_id),
+            Alias::new("shows"),
+        )
+        .expr_as(
+            count_query(
+                entity::season::Entity,
+                entity::season::Column::ShowId,
+                show_id,
+            ),
+            Alias::new("seasons"),
+        )
+        .expr_as(
+            count_query(
+                entity::episode::Entity,
+                entity::episode::Column::ShowId,
+                show_id,
+            ),
+            Alias::new("episodes"),
+        )
+        .expr_as(count_characters_query(show_id), Alias::new("characters"))
+        .expr_as(
+            count_query(
+                entity::quote::Entity,
+                entity::quote::Column::ShowId,
+                show_id,
+            ),
+            Alias::new("quotes"),
+        )
+        .expr_as(sub_query(parts_query), Alias::new("quote_parts"))
+        .to_owned();
+
+    db_backend.build(&query)
+}
+
+/// Counts the rows of `entity`, only counting those in the show when there is one.
+fn count_query<E, C>(entity: E, show_column: C, show_id: Option<i32>) -> SimpleExpr
+where
+    E: EntityTrait,
+    C: ColumnTrait,
+{
+    let query = Query::select()
+        .expr(Expr::col(Asterisk).count())
+        .from(entity)
+        .apply_if(show_id, |query, show_id| {
+            query.and_where(show_column.eq(show_id));
+        })
+        .to_owned();
+
+    sub_query(query)
+}
+
+/// Counts the characters, or those linked to the show through `character_show` when there is one.
+fn count_characters_query(show_id: Option<i32>) -> SimpleExpr {
+    return match show_id {
+        None => count_query(
+            entity::character::Entity,
+            entity::character::Column::Id,
+            None,
+        ),
+        Some(show_id) => count_query(
+            entity::character_show::Entity,
+            entity::character_show::Column::ShowId,
+            Some(show_id),
+        ),
+    };
+}
+
+fn sub_query(query: SelectStatement) -> SimpleExpr {
+    SimpleExpr::SubQuery(None, Box::new(query.into_sub_query_statement()))
+}
+
+fn build_top_characters_query(
+    show_id: Option<i32>,
+    top: u64,
+    db_backend: DatabaseBackend,
+) -> Statement {
+    // Characters are ranked within each show they're in, counting the quotes from that show.
+    // Quotes are counted once, no matter how many of their parts the character is involved in.
+    let query = entity::character::Entity::find()
+        .select_only()
+        .column_as(entity::show::Column::Name, "show_name")
+        .column(entity::character::Column::Name)
+        .column_as(
+            Expr::col((entity::quote::Entity, entity::quote::Column::Id))
+                .count_distinct()
+                .cast_as(Alias::new("integer")),
+            "quote_count",
+        )
+        .inner_join(entity::show::Entity)
+        .inner_join(entity::quote_part::Entity)
+        .join(JoinType::InnerJoin, quote_in_show())
+        .apply_if(show_id, |query, show_id| {
+            query.filter(entity::show::Column::Id.eq(show_id))
+        })
+        .group_by(entity::character::Column::Id)
+        .group_by(entity::character::Column::Name)
+        .group_by(entity::show::Column::Id)
+        .group_by(entity::show::Column::Name)
+        .order_by(Expr::col(Alias::new("quote_count")), Order::Desc)
+        .order_by_asc(entity::character::Column::Name)
+        .order_by_asc(entity::show::Column::Name)
+        .order_by_asc(entity::character::Column::Id)
+        .limit(top)
+        .as_query()
+        .to_owned();
+
+    db_backend.build(&query)
+}
+
+fn build_top_episodes_query(
+    show_id: Option<i32>,
+    top: u64,
+    db_backend: DatabaseBackend,
+) -> Statement {
+    let query = entity::episode::Entity::find()
+        .select_only()
+        .column_as(entity::show::Column::Name, "show_name")
+        .column(entity::season::Column::SeasonNo)
+        .column(entity::episode::Column::EpisodeNo)
+        .column_as(entity::episode::Column::Name, "episode_name")
+        .column_as(
+            Expr::col((entity::quote::Entity, entity::quote::Column::Id))
+                .count()
+                .cast_as(Alias::new("integer")),
+            "quote_count",
+        )
+        .inner_join(entity::show::Entity)
+        .inner_join(entity::season::Entity)
+        .inner_join(entity::quote::Entity)
+        .apply_if(show_id, |query, show_id| {
+            query.filter(entity::episode::Column::ShowId.eq(show_id))
+        })
+        .group_by(entity::episode::Column::Id)
+        .group_by(entity::episode::Column::EpisodeNo)
+        .group_by(entity::episode::Column::Name)
+        .group_by(entity::season::Column::SeasonNo)
+        .group_by(entity::show::Column::Name)
+        .order_by(Expr::col(Alias::new("quote_count")), Order::Desc)
+        .order_by_asc(entity::show::Column::Name)
+        .order_by_asc(entity::season::Column::SeasonNo)
+        .order_by_asc(entity::episode::Column::EpisodeNo)
+        .limit(top)
+        .as_query()
+        .to_owned();
+
+    db_backend.build(&query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn characters_are_counted_through_character_show() {
+        let stmt = build_totals_query(Some(1), DatabaseBackend::Postgres);
+        assert!(stmt.sql.contains(
+            r#"(SELECT COUNT(*) FROM "character_show" WHERE "character_show"."show_id" = $"#
+        ));
+
+        let stmt = build_totals_query(None, DatabaseBackend::Postgres);
+        assert!(stmt
+            .sql
+            .contains(r#"(SELECT COUNT(*) FROM "character") AS "characters""#));
+    }
+
+    #[test]
+    fn top_characters_are_ranked_within_the_show_through_character_show() {
+        let stmt = build_top_characters_query(Some(1), 10, DatabaseBackend::Postgres);
+
+        assert!(stmt.sql.contains(r#"INNER JOIN "character_show""#));
+        assert!(stmt.sql.contains(r#""quote"."show_id" = "show"."id""#));
+        assert!(stmt.sql.contains(r#""show"."id" = $1"#));
+        assert!(!stmt.sql.contains(r#""character"."show_id""#));
+    }
+}
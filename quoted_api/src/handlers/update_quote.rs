@@ -0,0 +1,86 @@
+use crate::{
+    api_response::{ApiResult, ErrorResult, SuccessResult},
+    auth::require_role,
+    db_helper::{create_quote_parts, delete_quote_parts, lock_for_id_allocation},
+    pipeline::{db_error, Context, Handler},
+};
+use http::Method;
+use quoted_api_models::{
+    auth::Role,
+    quote::{GetQuoteRequestParams, UpdateQuoteRequest, UpdateQuoteResponse},
+};
+use quoted_db_entity as entity;
+use sea_orm::{entity::*, DatabaseConnection, TransactionTrait};
+use tracing::info;
+
+use super::{create_quote::find_location, quote::find_quote};
+
+///
+/// Updates a quote, replacing its parts with the ones in the request.
+/// Requires the contributor role.
+///
+pub struct UpdateQuote;
+
+impl Handler for UpdateQuote {
+    type Params = GetQuoteRequestParams;
+    type Response = UpdateQuoteResponse;
+
+    const METHOD: Method = Method::PUT;
+
+    async fn call(params: GetQuoteRequestParams, ctx: &Context) -> ApiResult<UpdateQuoteResponse> {
+        require_role(ctx, Role::Contributor).await?;
+
+        let request = ctx.json::<UpdateQuoteRequest>()?;
+
+        save(&ctx.db, params.id, &request).await?;
+
+        return match find_quote(&ctx.db, params.id).await? {
+            None => Err(ErrorResult::server_error("Error updating quote")),
+            Some(quote) => Ok(SuccessResult::ok(quote)),
+        };
+    }
+}
+
+/// Updates the quote and replaces its parts in a single transaction.
+async fn save(
+    db: &DatabaseConnection,
+    quote_id: i32,
+    request: &UpdateQuoteRequest,
+) -> Result<(), ErrorResult> {
+    let save_error = |e| db_error(e, "Error updating quote");
+
+    let txn = db.begin().await.map_err(save_error)?;
+    lock_for_id_allocation(&txn).await.map_err(save_error)?;
+
+    let quote = entity::quote::Entity::find_by_id(quote_id)
+        .one(&txn)
+        .await
+        .map_err(save_error)?;
+    if quote.is_none() {
+        info!(id = quote_id, "Quote not found");
+        return Err(ErrorResult::not_found().with_message("Quote not found"));
+    }
+
+    let location = find_location(&txn, request).await?;
+
+    info!(id = quote_id, "Updating quote");
+    let quote = entity::quote::ActiveModel {
+        id: Unchanged(quote_id),
+        show_id: Set(location.show_id),
+        season_id: Set(location.season_id),
+        episode_id: Set(location.episode_id),
+        source_id: NotSet,
+    };
+    quote.update(&txn).await.map_err(save_error)?;
+
+    delete_quote_parts(&txn, quote_id)
+        .await
+        .map_err(save_error)?;
+    create_quote_parts(&txn, quote_id, location.show_id, &request.parts)
+        .await
+        .map_err(save_error)?;
+
+    txn.commit().await.map_err(save_error)?;
+
+    return Ok(());
+}
@@ -1,3 +1,11 @@
 pub mod api_response;
+pub mod auth;
+pub mod db_helper;
+pub mod error;
+pub mod handlers;
 pub mod models;
+pub mod observability;
+pub mod openapi;
+pub mod pipeline;
+pub mod runtime;
 pub mod setup;
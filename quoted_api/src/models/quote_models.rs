@@ -1,6 +1,14 @@
-use quoted_api_models::quote::{GetQuotesResponseItem, QuotePart};
-use sea_orm::FromQueryResult;
+use quoted_api_models::quote::{GetQuotesResponseItem, QuotePart, SearchQuotesResponseItem};
+use quoted_db_entity as entity;
+use sea_orm::{
+    prelude::Json,
+    sea_query::{Expr, SimpleExpr},
+    FromQueryResult, QuerySelect, Select,
+};
 use serde::{Deserialize, Serialize};
+use tracing::error;
+
+use crate::api_response::ErrorResult;
 
 #[derive(Deserialize, Serialize, Debug, Clone, FromQueryResult)]
 pub struct QuoteDBResult {
@@ -10,35 +18,101 @@ pub struct QuoteDBResult {
     pub season_name: Option<String>,
     pub episode_no: i32,
     pub episode_name: Option<String>,
+    pub parts: Json,
 }
 
 impl QuoteDBResult {
-    pub fn to_api_response(&self) -> GetQuotesResponseItem {
-        GetQuotesResponseItem {
+    pub fn to_api_response(&self) -> Result<GetQuotesResponseItem, ErrorResult> {
+        Ok(GetQuotesResponseItem {
+            id: self.quote_id,
             show_name: self.show_name.clone(),
             episode_name: self.episode_name.clone(),
             episode_no: self.episode_no,
             season_no: self.season_no,
             season_name: self.season_name.clone(),
-            parts: vec![],
-        }
+            parts: decode_parts(&self.parts)?,
+        })
     }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, FromQueryResult)]
-pub struct QuotePartDBResult {
+pub struct QuoteSearchDBResult {
     pub quote_id: i32,
-    pub character_name: String,
-    pub order: i32,
-    pub quote_text: String,
+    pub show_name: String,
+    pub season_no: i32,
+    pub season_name: Option<String>,
+    pub episode_no: i32,
+    pub episode_name: Option<String>,
+    pub parts: Json,
+    pub rank: f32,
+    pub snippet: String,
 }
 
-impl QuotePartDBResult {
-    pub fn to_api_response(&self) -> QuotePart {
-        QuotePart {
-            character_name: self.character_name.clone(),
-            order: self.order,
-            quote_text: self.quote_text.clone(),
-        }
+impl QuoteSearchDBResult {
+    pub fn to_api_response(&self) -> Result<SearchQuotesResponseItem, ErrorResult> {
+        Ok(SearchQuotesResponseItem {
+            quote: GetQuotesResponseItem {
+                id: self.quote_id,
+                show_name: self.show_name.clone(),
+                episode_name: self.episode_name.clone(),
+                episode_no: self.episode_no,
+                season_no: self.season_no,
+                season_name: self.season_name.clone(),
+                parts: decode_parts(&self.parts)?,
+            },
+            rank: self.rank,
+            snippet: self.snippet.clone(),
+        })
     }
 }
+
+///
+/// Selects the columns needed for a `QuoteDBResult`, so that a quote and all of
+/// its parts are fetched in a single query.
+///
+/// The query must have joined the episode, season and show of the quote.
+///
+pub fn select_quote_columns(query: Select<entity::quote::Entity>) -> Select<entity::quote::Entity> {
+    query
+        .select_only()
+        .column_as(entity::quote::Column::Id, "quote_id")
+        .column_as(entity::show::Column::Name, "show_name")
+        .column(entity::season::Column::SeasonNo)
+        .column_as(entity::season::Column::Name, "season_name")
+        .column(entity::episode::Column::EpisodeNo)
+        .column_as(entity::episode::Column::Name, "episode_name")
+        .expr_as(quote_parts_expr(), "parts")
+}
+
+///
+/// Builds an expression that aggregates the parts of the quote into a JSON array,
+/// ordered by `order_no`, with the same fields as `QuotePart`.
+///
+/// A sub query is used rather than a join, so that the quote query doesn't need
+/// to be grouped, and any grouping it already does isn't affected.
+///
+pub fn quote_parts_expr() -> SimpleExpr {
+    Expr::cust(concat!(
+        r#"(select coalesce(json_agg(json_build_object("#,
+        r#"'character_name', "character"."name", "#,
+        r#"'order', "quote_part"."order_no", "#,
+        r#"'quote_text', "quote_part"."value""#,
+        r#") order by "quote_part"."order_no"), '[]') "#,
+        r#"from "quote_part" "#,
+        r#"inner join "character" on "character"."id" = "quote_part"."character_id" "#,
+        r#"where "quote_part"."quote_id" = "quote"."id")"#,
+    ))
+}
+
+///
+/// Decodes the parts aggregated by `quote_parts_expr`.
+///
+pub fn decode_parts(parts: &Json) -> Result<Vec<QuotePart>, ErrorResult> {
+    return match serde_json::from_value::<Vec<QuotePart>>(parts.clone()) {
+        Err(e) => {
+            error!(error = %e, "Error decoding quote parts");
+            Err(ErrorResult::server_error("Error reading quote parts"))
+        }
+        Ok(parts) => Ok(parts),
+    };
+}
@@ -0,0 +1,200 @@
+use std::{env, sync::OnceLock, time::Duration};
+
+use http::HeaderMap;
+use sea_orm::{metric::Info, DatabaseConnection};
+use tracing::{debug, info_span, warn};
+use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
+
+///
+/// The env var with the log levels, using the `tracing_subscriber` directive syntax,
+/// e.g. `info` or `info,quoted_api=debug`.
+///
+const LOG_LEVEL_ENV_VAR: &str = "LOG_LEVEL";
+
+///
+/// The env var with the format of the logs, which is either `json` or `text`.
+///
+const LOG_FORMAT_ENV_VAR: &str = "LOG_FORMAT";
+
+///
+/// The env var with the number of milliseconds after which a query is logged as slow.
+///
+const SLOW_QUERY_ENV_VAR: &str = "SLOW_QUERY_MS";
+
+const DEFAULT_LOG_LEVEL: &str = "info";
+const DEFAULT_SLOW_QUERY: Duration = Duration::from_millis(500);
+
+///
+/// The header that the ID of the request is returned in.
+///
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+///
+/// Headers that may already identify the request, in order of preference.
+/// Vercel sets `x-vercel-id` on every request, which makes the logs easy to
+/// match up with the request in the Vercel dashboard.
+///
+const REQUEST_ID_HEADERS: [&str; 2] = ["x-vercel-id", REQUEST_ID_HEADER];
+
+///
+/// Installs the global `tracing` subscriber, logging JSON to stdout at the levels
+/// in `LOG_LEVEL`, which defaults to `info`. Set `LOG_FORMAT=text` for logs that
+/// are easier to read locally.
+///
+/// Calling this again has no effect, so warm serverless invocations can call it freely.
+///
+pub fn init(extra_directives: &[&str]) {
+    let mut filter = EnvFilter::try_from_env(LOG_LEVEL_ENV_VAR)
+        .unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_LEVEL));
+    for directive in extra_directives {
+        if let Ok(directive) = directive.parse() {
+            filter = filter.add_directive(directive);
+        }
+    }
+
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+
+    let _ = match env::var(LOG_FORMAT_ENV_VAR).as_deref() {
+        Ok("text") => builder.try_init(),
+        _ => builder
+            .json()
+            .flatten_event(true)
+            .with_current_span(false)
+            .with_span_list(true)
+            .try_init(),
+    };
+}
+
+///
+/// Gets the ID of the request from the headers set by Vercel or the client,
+/// generating a new one when there isn't one.
+///
+pub fn request_id(headers: &HeaderMap) -> String {
+    REQUEST_ID_HEADERS
+        .iter()
+        .find_map(|name| headers.get(*name)?.to_str().ok())
+        .filter(|id| !id.is_empty())
+        .map(str::to_owned)
+        .unwrap_or_else(|| Uuid::new_v4().to_string())
+}
+
+///
+/// Records a `db_query` span for every query run on the connection once it has
+/// finished, with the SQL and its duration, and logs the query within it, warning
+/// about queries slower than `SLOW_QUERY_MS`, which defaults to `500`.
+///
+/// The spans are within the span of the request that ran the query. The values
+/// bound to the query are left out, as they can include passwords and tokens.
+///
+pub fn trace_queries(db: &mut DatabaseConnection) {
+    let slow_query = slow_query_threshold();
+
+    db.set_metric_callback(move |info: &Info<'_>| log_query(info, slow_query));
+}
+
+fn log_query(info: &Info<'_>, slow_query: Duration) {
+    // sea-orm only calls back once the query has finished, so the span is
+    // created with the duration rather than being open while the query runs
+    let span = info_span!(
+        "db_query",
+        sql = %info.statement.sql,
+        duration_ms = info.elapsed.as_millis() as u64,
+        failed = info.failed,
+    );
+    let _entered = span.enter();
+
+    match info.elapsed >= slow_query {
+        true => warn!("Slow query"),
+        false => debug!("Query finished"),
+    }
+}
+
+fn slow_query_threshold() -> Duration {
+    static SLOW_QUERY: OnceLock<Duration> = OnceLock::new();
+
+    *SLOW_QUERY.get_or_init(|| match env::var(SLOW_QUERY_ENV_VAR) {
+        Err(_) => DEFAULT_SLOW_QUERY,
+        Ok(ms) => match ms.parse::<u64>() {
+            Ok(ms) => Duration::from_millis(ms),
+            Err(_) => {
+                warn!(
+                    "Invalid value for {}, using the default",
+                    SLOW_QUERY_ENV_VAR
+                );
+                DEFAULT_SLOW_QUERY
+            }
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DbBackend, Statement};
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    #[derive(Clone, Default)]
+    struct Logs(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Logs {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn log(elapsed: Duration) -> serde_json::Value {
+        let logs = Logs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_env_filter(EnvFilter::new("debug"))
+            .with_writer(move || writer.clone())
+            .json()
+            .flatten_event(true)
+            .with_span_list(true)
+            .finish();
+
+        let statement = Statement::from_string(DbBackend::Postgres, "SELECT 1");
+        tracing::subscriber::with_default(subscriber, || {
+            log_query(
+                &Info {
+                    elapsed,
+                    statement: &statement,
+                    failed: false,
+                },
+                DEFAULT_SLOW_QUERY,
+            )
+        });
+
+        let logs = logs.0.lock().unwrap();
+        serde_json::from_slice(&logs).unwrap()
+    }
+
+    #[test]
+    fn queries_are_logged_in_a_span_with_their_duration() {
+        let log = log(Duration::from_millis(12));
+
+        assert_eq!(log["level"], "DEBUG");
+        assert_eq!(log["message"], "Query finished");
+        assert_eq!(log["spans"][0]["name"], "db_query");
+        assert_eq!(log["spans"][0]["sql"], "SELECT 1");
+        assert_eq!(log["spans"][0]["duration_ms"], 12);
+        assert_eq!(log["spans"][0]["failed"], false);
+    }
+
+    #[test]
+    fn slow_queries_are_warnings() {
+        let log = log(DEFAULT_SLOW_QUERY);
+
+        assert_eq!(log["level"], "WARN");
+        assert_eq!(log["message"], "Slow query");
+        assert_eq!(log["spans"][0]["duration_ms"], 500);
+    }
+}
@@ -0,0 +1,454 @@
+use quoted_api_models::{
+    auth::{LoginRequestBody, LoginResponseBody},
+    character::{
+        GetCharactersInShowRequest, GetCharactersInShowRequestParams,
+        GetCharactersInShowResponseItem,
+    },
+    episode::{
+        GetEpisodesInSeasonRequest, GetEpisodesInSeasonRequestParams,
+        GetEpisodesInSeasonResponseItem,
+    },
+    health::GetHealthResponseBody,
+    page::{PagedResponse, SortDirection},
+    quote::{
+        GetDailyQuoteRequestParams, GetQuoteRequestParams, GetQuotesParams, GetQuotesResponseItem,
+        GetRandomQuoteRequestParams, QuoteSort, SaveQuoteRequestBody, SearchQuotesParams,
+        SearchQuotesResponseItem,
+    },
+    season::{
+        GetSeasonsInShowRequest, GetSeasonsInShowRequestParams, GetSeasonsInShowResponseItem,
+    },
+    show::{GetShowsRequest, GetShowsRequestParams, GetShowsResponseItem},
+    stats::{GetStatsRequestParams, GetStatsResponseBody},
+};
+use quoted_format::Format;
+use serde_json::Value;
+use utoipa::{
+    openapi::{
+        path::{Operation, ParameterIn, PathItem},
+        security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+        Content, ObjectBuilder, Ref, RefOr, Required, ResponseBuilder, Type,
+    },
+    Modify, OpenApi,
+};
+
+use crate::{api_response::ErrorDetail, pipeline::FormatParams};
+
+///
+/// The OpenAPI document for the API, with schemas derived from the request and
+/// response models in `quoted_api_models`.
+///
+/// `openapi.json` in the root of the crate is a copy of the document, which the
+/// `openapi` test checks is up to date.
+///
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Quoted API",
+        description = "Quotes from your favourite TV shows."
+    ),
+    paths(
+        paths::login,
+        paths::get_shows,
+        paths::get_seasons_in_show,
+        paths::get_characters_in_show,
+        paths::get_episodes_in_season,
+        paths::get_quotes,
+        paths::create_quote,
+        paths::search_quotes,
+        paths::get_random_quote,
+        paths::get_daily_quote,
+        paths::get_quote,
+        paths::update_quote,
+        paths::delete_quote,
+        paths::get_stats,
+        paths::get_openapi,
+        paths::get_health,
+    ),
+    // Schemas that are only used by parameters aren't picked up from the paths
+    components(schemas(ErrorDetail, QuoteSort, SortDirection, Format)),
+    modifiers(
+        &PathParams,
+        &BearerAuth,
+        &ErrorResponses,
+        &QuoteFormats,
+        &TrimDescriptions
+    )
+)]
+pub struct ApiDoc;
+
+///
+/// Builds the OpenAPI document.
+///
+pub fn document() -> utoipa::openapi::OpenApi {
+    let mut document = ApiDoc::openapi();
+
+    // The crate doesn't have a license, which utoipa would otherwise include with an empty name
+    document.info.license = None;
+
+    document
+}
+
+///
+/// Builds the OpenAPI document as pretty printed JSON, as it's saved in `openapi.json`.
+///
+pub fn document_json() -> String {
+    document().to_pretty_json().unwrap_or_default() + "\n"
+}
+
+// Handlers implement the `Handler` trait, and `utoipa::path` can't be applied to
+// trait methods, so each handler is described by an empty function here instead.
+#[allow(dead_code)]
+mod paths {
+    use super::*;
+
+    /// Logs a user in, issuing a token that can be used to call protected endpoints.
+    #[utoipa::path(
+        post,
+        path = "/api/auth/login",
+        tag = "auth",
+        request_body = LoginRequestBody,
+        responses(
+            (status = 200, description = "The user was logged in", body = LoginResponseBody),
+        )
+    )]
+    fn login() {}
+
+    /// Lists the available shows.
+    #[utoipa::path(
+        get,
+        path = "/api/shows",
+        tag = "shows",
+        params(GetShowsRequest, GetShowsRequestParams),
+        responses(
+            (status = 200, description = "A page of shows", body = PagedResponse<GetShowsResponseItem>),
+        )
+    )]
+    fn get_shows() {}
+
+    /// Lists the seasons within a show.
+    #[utoipa::path(
+        get,
+        path = "/api/show/{show}/seasons",
+        tag = "shows",
+        params(GetSeasonsInShowRequest, GetSeasonsInShowRequestParams),
+        responses(
+            (status = 200, description = "A page of seasons", body = PagedResponse<GetSeasonsInShowResponseItem>),
+        )
+    )]
+    fn get_seasons_in_show() {}
+
+    /// Lists the characters within a show.
+    #[utoipa::path(
+        get,
+        path = "/api/show/{show}/characters",
+        tag = "shows",
+        params(GetCharactersInShowRequest, GetCharactersInShowRequestParams),
+        responses(
+            (status = 200, description = "A page of characters", body = PagedResponse<GetCharactersInShowResponseItem>),
+        )
+    )]
+    fn get_characters_in_show() {}
+
+    /// Lists the episodes within a season of a show.
+    #[utoipa::path(
+        get,
+        path = "/api/show/{show}/season/{season}/episodes",
+        tag = "shows",
+        params(GetEpisodesInSeasonRequest, GetEpisodesInSeasonRequestParams),
+        responses(
+            (status = 200, description = "A page of episodes", body = PagedResponse<GetEpisodesInSeasonResponseItem>),
+        )
+    )]
+    fn get_episodes_in_season() {}
+
+    /// Lists the quotes within a show, season or episode.
+    #[utoipa::path(
+        get,
+        path = "/api/quotes",
+        tag = "quotes",
+        params(GetQuotesParams, FormatParams),
+        responses(
+            (status = 200, description = "A page of quotes", body = PagedResponse<GetQuotesResponseItem>),
+        )
+    )]
+    fn get_quotes() {}
+
+    /// Creates a quote, along with any characters in it that don't yet exist in the show.
+    /// Requires the contributor role.
+    #[utoipa::path(
+        post,
+        path = "/api/quotes",
+        tag = "quotes",
+        request_body = SaveQuoteRequestBody,
+        security(("bearer" = [])),
+        responses(
+            (status = 201, description = "The quote that was created", body = GetQuotesResponseItem),
+        )
+    )]
+    fn create_quote() {}
+
+    /// Searches the text of quotes, returning the best matches first.
+    #[utoipa::path(
+        get,
+        path = "/api/quotes/search",
+        tag = "quotes",
+        params(SearchQuotesParams, FormatParams),
+        responses(
+            (status = 200, description = "A page of matching quotes", body = PagedResponse<SearchQuotesResponseItem>),
+        )
+    )]
+    fn search_quotes() {}
+
+    /// Gets random quotes, optionally from a given show, season, episode or character.
+    #[utoipa::path(
+        get,
+        path = "/api/quote/random",
+        tag = "quotes",
+        params(GetRandomQuoteRequestParams, FormatParams),
+        responses(
+            (status = 200, description = "The random quotes", body = Vec<GetQuotesResponseItem>),
+        )
+    )]
+    fn get_random_quote() {}
+
+    /// Gets the quote of the day, optionally from a given show or character.
+    #[utoipa::path(
+        get,
+        path = "/api/quote/daily",
+        tag = "quotes",
+        params(GetDailyQuoteRequestParams, FormatParams),
+        responses(
+            (status = 200, description = "The quote of the day", body = GetQuotesResponseItem),
+        )
+    )]
+    fn get_daily_quote() {}
+
+    /// Gets a single quote by its ID.
+    #[utoipa::path(
+        get,
+        path = "/api/quote/{id}",
+        tag = "quotes",
+        params(GetQuoteRequestParams, FormatParams),
+        responses(
+            (status = 200, description = "The quote", body = GetQuotesResponseItem),
+        )
+    )]
+    fn get_quote() {}
+
+    /// Updates a quote, replacing its parts with the ones in the request.
+    /// Requires the contributor role.
+    #[utoipa::path(
+        put,
+        path = "/api/quote/{id}",
+        tag = "quotes",
+        params(GetQuoteRequestParams),
+        request_body = SaveQuoteRequestBody,
+        security(("bearer" = [])),
+        responses(
+            (status = 200, description = "The quote after it was updated", body = GetQuotesResponseItem),
+        )
+    )]
+    fn update_quote() {}
+
+    /// Deletes a quote along with its parts, returning the quote as it was.
+    /// Requires the admin role.
+    #[utoipa::path(
+        delete,
+        path = "/api/quote/{id}",
+        tag = "quotes",
+        params(GetQuoteRequestParams),
+        security(("bearer" = [])),
+        responses(
+            (status = 200, description = "The quote before it was deleted", body = GetQuotesResponseItem),
+        )
+    )]
+    fn delete_quote() {}
+
+    /// Gets statistics about the quotes in the catalogue, optionally limited to a single show.
+    #[utoipa::path(
+        get,
+        path = "/api/stats",
+        tag = "stats",
+        params(GetStatsRequestParams),
+        responses(
+            (status = 200, description = "The statistics", body = GetStatsResponseBody),
+        )
+    )]
+    fn get_stats() {}
+
+    /// Gets this OpenAPI document.
+    #[utoipa::path(
+        get,
+        path = "/api/openapi.json",
+        tag = "meta",
+        responses(
+            (status = 200, description = "The OpenAPI document", body = Object),
+        )
+    )]
+    fn get_openapi() {}
+
+    /// Checks whether the API can serve requests, i.e. whether the database can be
+    /// reached and has every migration in the deployment applied to it.
+    #[utoipa::path(
+        get,
+        path = "/api/health",
+        tag = "meta",
+        responses(
+            (status = 200, description = "The API can serve requests", body = GetHealthResponseBody),
+            (status = 503, description = "The database can't be reached or has pending migrations", body = GetHealthResponseBody),
+        )
+    )]
+    fn get_health() {}
+}
+
+///
+/// Path parameters (e.g. `{show}`) are passed to handlers as query parameters, so
+/// the parameter structs describe them as query parameters. This moves them to the path.
+///
+struct PathParams;
+
+impl Modify for PathParams {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for (path, item) in openapi.paths.paths.iter_mut() {
+            for operation in operations_mut(item) {
+                for param in operation.parameters.iter_mut().flatten() {
+                    if path.contains(&format!("{{{}}}", param.name)) {
+                        param.parameter_in = ParameterIn::Path;
+                        param.required = Required::True;
+                    }
+                }
+            }
+        }
+    }
+}
+
+///
+/// Describes the bearer token used by endpoints that require a role.
+///
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "bearer",
+                SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+            );
+        }
+    }
+}
+
+///
+/// Every endpoint returns an `ErrorDetail` when the request fails.
+///
+struct ErrorResponses;
+
+impl Modify for ErrorResponses {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for item in openapi.paths.paths.values_mut() {
+            for operation in operations_mut(item) {
+                operation.responses.responses.insert(
+                    "default".to_owned(),
+                    ResponseBuilder::new()
+                        .description("The request failed")
+                        .content(
+                            "application/json",
+                            Content::new(Some(Ref::from_schema_name("ErrorDetail"))),
+                        )
+                        .build()
+                        .into(),
+                );
+            }
+        }
+    }
+}
+
+///
+/// Endpoints that accept the `format` parameter can also return quotes as
+/// plain text, CSV or NDJSON.
+///
+struct QuoteFormats;
+
+impl Modify for QuoteFormats {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let formats = [Format::Text, Format::Csv, Format::Ndjson];
+
+        for item in openapi.paths.paths.values_mut() {
+            for operation in operations_mut(item) {
+                let has_format = operation
+                    .parameters
+                    .iter()
+                    .flatten()
+                    .any(|param| param.name == "format");
+                if !has_format {
+                    continue;
+                }
+
+                for (status, response) in operation.responses.responses.iter_mut() {
+                    let RefOr::T(response) = response else {
+                        continue;
+                    };
+                    if !status.starts_with('2') {
+                        continue;
+                    }
+                    for format in formats {
+                        response.content.insert(
+                            format.content_type().to_owned(),
+                            Content::new(Some(ObjectBuilder::new().schema_type(Type::String))),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+///
+/// Doc comments start and end with an empty `///` line, which utoipa keeps as blank
+/// lines at either end of the descriptions taken from them.
+///
+struct TrimDescriptions;
+
+impl Modify for TrimDescriptions {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let Ok(mut value) = serde_json::to_value(&*openapi) else {
+            return;
+        };
+        trim_descriptions(&mut value);
+        if let Ok(trimmed) = serde_json::from_value(value) {
+            *openapi = trimmed;
+        }
+    }
+}
+
+fn trim_descriptions(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            for (name, value) in fields.iter_mut() {
+                match value {
+                    Value::String(description) if name == "description" => {
+                        *description = description.trim().to_owned();
+                    }
+                    value => trim_descriptions(value),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(trim_descriptions),
+        _ => {}
+    }
+}
+
+fn operations_mut(item: &mut PathItem) -> impl Iterator<Item = &mut Operation> {
+    [
+        &mut item.get,
+        &mut item.put,
+        &mut item.post,
+        &mut item.delete,
+        &mut item.patch,
+        &mut item.head,
+        &mut item.options,
+    ]
+    .into_iter()
+    .flatten()
+}
@@ -0,0 +1,506 @@
+use std::{fmt::Debug, future::Future, time::Instant};
+
+use http::{header::CONTENT_LENGTH, HeaderMap, HeaderValue, Method};
+use quoted_api_models::validate::{Rules, Validate};
+use quoted_format::Format;
+use sea_orm::{DatabaseConnection, DbErr, FromQueryResult, Statement};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tracing::{debug, error, info, info_span, Instrument};
+use utoipa::IntoParams;
+use vercel_runtime::{Body, Error, Request, Response};
+
+use crate::{
+    api_response::{ApiResult, ErrorResult, VercelResponse},
+    error::{ApiError, ErrorCode},
+    observability::{self, REQUEST_ID_HEADER},
+};
+
+pub mod cors;
+pub mod pagination;
+pub mod rate_limit;
+
+use cors::AllowedOrigins;
+use rate_limit::{check_rate_limit, RateLimit};
+
+///
+/// The details of a request that are made available to a `Handler`,
+/// regardless of the runtime hosting it.
+///
+pub struct Context {
+    ///
+    /// The connection to the database.
+    ///
+    pub db: DatabaseConnection,
+
+    ///
+    /// The headers that were sent with the request.
+    ///
+    pub headers: HeaderMap,
+
+    ///
+    /// The body that was sent with the request.
+    ///
+    pub body: Body,
+}
+
+impl Context {
+    ///
+    /// Parses the JSON body of the request into `T`.
+    ///
+    /// # Errors
+    ///
+    /// When the request has no body, the body cannot be parsed into `T`, or it
+    /// doesn't follow the rules of `T`.
+    ///
+    pub fn json<T>(&self) -> Result<T, ErrorResult>
+    where
+        T: DeserializeOwned + Validate + Debug,
+    {
+        let bytes: &[u8] = match &self.body {
+            Body::Empty => &[],
+            Body::Text(text) => text.as_bytes(),
+            Body::Binary(bytes) => bytes,
+        };
+
+        if bytes.is_empty() {
+            return Err(ErrorResult::new(
+                ErrorCode::InvalidBody,
+                "Missing request body",
+            ));
+        }
+
+        let deserializer = &mut serde_json::Deserializer::from_slice(bytes);
+        return match serde_path_to_error::deserialize::<_, T>(deserializer) {
+            Err(e) => Err(ApiError::from(e).into()),
+            Ok(body) => {
+                debug!(?body, "Parsed body");
+                body.validate()?;
+                Ok(body)
+            }
+        };
+    }
+}
+
+///
+/// Defines how a request to an API endpoint is handled, independently of the
+/// runtime that hosts it.
+///
+/// Implementations describe the parameters they accept and the work to be done.
+/// Parameter parsing and building the HTTP response are taken care of by `handle`.
+///
+pub trait Handler {
+    ///
+    /// The parameters accepted by the endpoint, parsed from the query string.
+    /// Dynamic path segments (e.g. `[show]`) are passed to the handler as query
+    /// parameters, so they should be included here too.
+    ///
+    /// The parameters are validated before the handler is called.
+    ///
+    type Params: DeserializeOwned + Validate + Debug + Send;
+
+    ///
+    /// The body returned when the request succeeds.
+    ///
+    type Response: Serialize;
+
+    ///
+    /// The HTTP method handled.
+    ///
+    const METHOD: Method = Method::GET;
+
+    ///
+    /// How many requests each client can make to the endpoint.
+    ///
+    const RATE_LIMIT: RateLimit = RateLimit::DEFAULT;
+
+    ///
+    /// Whether the handler needs a connection pool to run. Handlers that don't are
+    /// given a disconnected `DatabaseConnection` when the pool can't be created,
+    /// which returns an error whenever it's used.
+    ///
+    const REQUIRES_DB: bool = true;
+
+    ///
+    /// Handles the request.
+    ///
+    fn call(
+        params: Self::Params,
+        ctx: &Context,
+    ) -> impl Future<Output = ApiResult<Self::Response>> + Send;
+}
+
+///
+/// Parameters for handlers that don't accept any.
+///
+#[derive(Debug, Deserialize)]
+pub struct NoParams {}
+
+impl Validate for NoParams {
+    fn rules(&self, _rules: &mut Rules) {}
+}
+
+///
+/// A path served by the API, made up of one `Handler` per supported HTTP method.
+///
+/// Every `Handler` is an endpoint on its own. Handlers for the same path are
+/// combined into a tuple, e.g. `(GetQuotes, CreateQuote)`.
+///
+pub trait Endpoint {
+    ///
+    /// The HTTP methods supported by the endpoint.
+    ///
+    fn methods() -> Vec<Method>;
+
+    ///
+    /// Whether any of the handlers need a connection pool to run.
+    ///
+    fn requires_db() -> bool;
+
+    ///
+    /// Runs the request through the handler for its method.
+    ///
+    fn dispatch(
+        req: Request,
+        db: DatabaseConnection,
+    ) -> impl Future<Output = Result<Response<Body>, Error>> + Send;
+}
+
+impl<H> Endpoint for H
+where
+    H: Handler,
+{
+    fn methods() -> Vec<Method> {
+        vec![H::METHOD]
+    }
+
+    fn requires_db() -> bool {
+        H::REQUIRES_DB
+    }
+
+    async fn dispatch(req: Request, db: DatabaseConnection) -> Result<Response<Body>, Error> {
+        if *req.method() != H::METHOD {
+            return ErrorResult::method_not_allowed(&[H::METHOD]).vercel();
+        }
+        if let Err(e) = check_rate_limit::<H>(req.headers(), &db, H::RATE_LIMIT).await {
+            return e.vercel();
+        }
+
+        let params = match parse_query::<H::Params>(&req) {
+            Err(e) => return e.vercel(),
+            Ok(p) => p,
+        };
+        if let Err(errors) = params.validate() {
+            return ErrorResult::from(errors).vercel();
+        }
+        let format = match parse_format(&req) {
+            Err(e) => return e.vercel(),
+            Ok(format) => format,
+        };
+
+        let (parts, body) = req.into_parts();
+        let ctx = Context {
+            db,
+            headers: parts.headers,
+            body,
+        };
+
+        return match H::call(params, &ctx).await {
+            Err(e) => e.vercel(),
+            Ok(result) => result.respond(&ctx.headers, format),
+        };
+    }
+}
+
+impl<A, B> Endpoint for (A, B)
+where
+    A: Handler,
+    B: Handler,
+{
+    fn methods() -> Vec<Method> {
+        vec![A::METHOD, B::METHOD]
+    }
+
+    fn requires_db() -> bool {
+        A::REQUIRES_DB || B::REQUIRES_DB
+    }
+
+    async fn dispatch(req: Request, db: DatabaseConnection) -> Result<Response<Body>, Error> {
+        if *req.method() == A::METHOD {
+            return A::dispatch(req, db).await;
+        }
+        B::dispatch(req, db).await
+    }
+}
+
+impl<A, B, C> Endpoint for (A, B, C)
+where
+    A: Handler,
+    B: Handler,
+    C: Handler,
+{
+    fn methods() -> Vec<Method> {
+        vec![A::METHOD, B::METHOD, C::METHOD]
+    }
+
+    fn requires_db() -> bool {
+        A::REQUIRES_DB || B::REQUIRES_DB || C::REQUIRES_DB
+    }
+
+    async fn dispatch(req: Request, db: DatabaseConnection) -> Result<Response<Body>, Error> {
+        if *req.method() == A::METHOD {
+            return A::dispatch(req, db).await;
+        }
+        <(B, C)>::dispatch(req, db).await
+    }
+}
+
+///
+/// Runs the request through the endpoint `E`, returning the HTTP response to be
+/// sent back to the client.
+///
+/// Everything logged while handling the request is within a `request` span with
+/// the ID of the request, which is also returned in the `X-Request-Id` header.
+///
+/// The HTTP semantics shared by every endpoint are handled here rather than by
+/// the handlers, i.e. `OPTIONS` (including CORS preflight requests), `HEAD` for
+/// endpoints that support `GET`, `405 Method Not Allowed` for other methods, and
+/// the CORS headers of every response.
+///
+pub async fn handle<E>(
+    mut req: Request,
+    mut db: DatabaseConnection,
+) -> Result<Response<Body>, Error>
+where
+    E: Endpoint,
+{
+    let request_id = observability::request_id(req.headers());
+    let span = info_span!(
+        "request",
+        request_id = %request_id,
+        method = %req.method(),
+        path = %req.uri().path(),
+    );
+    observability::trace_queries(&mut db);
+
+    async move {
+        let started = Instant::now();
+        info!("Request received");
+
+        let methods = allowed_methods(&E::methods());
+        let request_headers = req.headers().clone();
+
+        let mut res = match req.method().clone() {
+            Method::OPTIONS => cors::preflight(&methods),
+            method if !methods.contains(&method) => {
+                ErrorResult::method_not_allowed(&methods).vercel()
+            }
+            Method::HEAD => {
+                // Responds exactly as `GET` would, without the body
+                *req.method_mut() = Method::GET;
+                E::dispatch(req, db).await.map(without_body)
+            }
+            _ => E::dispatch(req, db).await,
+        };
+
+        match &mut res {
+            Err(e) => error!(error = %e, "Error building response"),
+            Ok(res) => {
+                cors::add_cors_headers(
+                    AllowedOrigins::from_env(),
+                    &request_headers,
+                    res.headers_mut(),
+                );
+                if let Ok(value) = HeaderValue::from_str(&request_id) {
+                    res.headers_mut().insert(REQUEST_ID_HEADER, value);
+                }
+                info!(
+                    status = res.status().as_u16(),
+                    duration_ms = started.elapsed().as_millis() as u64,
+                    "Request finished"
+                );
+            }
+        }
+
+        res
+    }
+    .instrument(span)
+    .await
+}
+
+///
+/// Removes the body of a `GET` response so that it can be returned for a `HEAD` request,
+/// keeping the `Content-Length` the body would have had.
+///
+fn without_body(mut res: Response<Body>) -> Response<Body> {
+    let length = match res.body() {
+        Body::Empty => 0,
+        Body::Text(text) => text.len(),
+        Body::Binary(bytes) => bytes.len(),
+    };
+    res.headers_mut()
+        .insert(CONTENT_LENGTH, HeaderValue::from(length));
+    *res.body_mut() = Body::Empty;
+
+    res
+}
+
+///
+/// The methods supported by an endpoint that handles the given methods, which
+/// also include `HEAD` when `GET` is handled, and `OPTIONS`.
+///
+fn allowed_methods(handled: &[Method]) -> Vec<Method> {
+    let mut methods = handled.to_vec();
+    if methods.contains(&Method::GET) && !methods.contains(&Method::HEAD) {
+        methods.push(Method::HEAD);
+    }
+    if !methods.contains(&Method::OPTIONS) {
+        methods.push(Method::OPTIONS);
+    }
+    methods
+}
+
+///
+/// Parses the query string of the request into `T`.
+/// A request without a query string is treated as having an empty one.
+///
+/// # Errors
+///
+/// When the query string cannot be parsed into `T`.
+///
+pub fn parse_query<T>(req: &Request) -> Result<T, ErrorResult>
+where
+    T: DeserializeOwned + Debug,
+{
+    let query = req.uri().query().unwrap_or_default();
+    debug!(query, "Parsing query params");
+
+    let deserializer =
+        serde_urlencoded::Deserializer::new(form_urlencoded::parse(query.as_bytes()));
+    return match serde_path_to_error::deserialize::<_, T>(deserializer) {
+        Err(e) => Err(ApiError::from(e).into()),
+        Ok(params) => {
+            debug!(?params, "Parsed query");
+            Ok(params)
+        }
+    };
+}
+
+///
+/// The `format` query parameter, which is accepted by every endpoint alongside its own parameters.
+///
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FormatParams {
+    ///
+    /// The format to return quotes in, taking priority over the `Accept` header.
+    /// Only supported by endpoints that return quotes.
+    ///
+    pub format: Option<Format>,
+}
+
+///
+/// Parses the format the response was requested in from the query string, if there is one.
+///
+/// # Errors
+///
+/// When the format isn't one of the supported formats.
+///
+fn parse_format(req: &Request) -> Result<Option<Format>, ErrorResult> {
+    return match parse_query::<FormatParams>(req) {
+        Err(_) => Err(
+            ErrorResult::new(ErrorCode::InvalidParameters, "Unsupported format")
+                .with_field("format"),
+        ),
+        Ok(params) => Ok(params.format),
+    };
+}
+
+///
+/// Executes the statement, returning every row as `T`.
+///
+/// # Errors
+///
+/// When the database returns an error, in which case a server error
+/// with the given `error_message` is returned.
+///
+pub async fn fetch_all<T>(
+    db: &DatabaseConnection,
+    stmt: Statement,
+    error_message: &str,
+) -> Result<Vec<T>, ErrorResult>
+where
+    T: FromQueryResult,
+{
+    return match T::find_by_statement(stmt).all(db).await {
+        Err(e) => Err(db_error(e, error_message)),
+        Ok(rows) => Ok(rows),
+    };
+}
+
+///
+/// Executes the statement, returning the first row as `T`, if there is one.
+///
+/// # Errors
+///
+/// When the database returns an error, in which case a server error
+/// with the given `error_message` is returned.
+///
+pub async fn fetch_one<T>(
+    db: &DatabaseConnection,
+    stmt: Statement,
+    error_message: &str,
+) -> Result<Option<T>, ErrorResult>
+where
+    T: FromQueryResult,
+{
+    return match T::find_by_statement(stmt).one(db).await {
+        Err(e) => Err(db_error(e, error_message)),
+        Ok(row) => Ok(row),
+    };
+}
+
+///
+/// Logs the database error, returning the matching error code. Errors that don't
+/// have a more specific code are returned with the given `error_message`.
+///
+pub fn db_error(e: DbErr, error_message: &str) -> ErrorResult {
+    let error = ErrorResult::from(ApiError::Database(e));
+
+    return match error.body.code {
+        ErrorCode::DatabaseError => error.with_message(error_message),
+        _ => error,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::header::ETAG;
+
+    #[test]
+    fn head_responses_keep_the_length_and_headers_of_the_body() {
+        let res = Response::builder()
+            .status(200)
+            .header(ETAG, "\"abc\"")
+            .body(Body::Text("{\"name\":\"Futurama\"}".to_owned()))
+            .unwrap();
+
+        let res = without_body(res);
+
+        assert!(matches!(res.body(), Body::Empty));
+        assert_eq!(res.headers()[CONTENT_LENGTH], "19");
+        assert_eq!(res.headers()[ETAG], "\"abc\"");
+    }
+
+    #[test]
+    fn head_and_options_are_allowed_with_get() {
+        assert_eq!(
+            allowed_methods(&[Method::GET, Method::POST]),
+            vec![Method::GET, Method::POST, Method::HEAD, Method::OPTIONS]
+        );
+        assert_eq!(
+            allowed_methods(&[Method::POST]),
+            vec![Method::POST, Method::OPTIONS]
+        );
+    }
+}
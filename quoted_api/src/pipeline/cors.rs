@@ -0,0 +1,214 @@
+use std::{env, sync::OnceLock};
+
+use http::{
+    header::{
+        ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN,
+        ACCESS_CONTROL_EXPOSE_HEADERS, ACCESS_CONTROL_MAX_AGE, ALLOW, ORIGIN, VARY,
+    },
+    HeaderMap, HeaderValue, Method,
+};
+use vercel_runtime::{Body, Error, Response};
+
+///
+/// The env var with the origins that browser apps can call the API from,
+/// separated by commas, e.g. `https://quoted.example.com,http://localhost:5173`.
+/// `*` allows any origin.
+///
+const CORS_ORIGINS_ENV_VAR: &str = "CORS_ALLOWED_ORIGINS";
+
+///
+/// The origin allowed when `CORS_ALLOWED_ORIGINS` isn't set, which is the UI.
+///
+const DEFAULT_ORIGIN: &str = "https://devklick-quoted.vercel.app";
+
+///
+/// The request headers that browser apps can send, in addition to the ones
+/// that are always allowed.
+///
+const ALLOWED_HEADERS: &str =
+    "Authorization, Content-Type, If-None-Match, If-Modified-Since, X-Request-Id";
+
+///
+/// The response headers that browser apps can read, in addition to the ones
+/// that are always exposed.
+///
+const EXPOSED_HEADERS: &str = "ETag, Retry-After, X-Request-Id";
+
+///
+/// How many seconds browsers can cache the response to a preflight request for.
+///
+const PREFLIGHT_MAX_AGE: &str = "86400";
+
+///
+/// The origins that are allowed to call the API from a browser.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AllowedOrigins {
+    ///
+    /// Any origin can call the API. Credentials aren't used by the API, so this is
+    /// safe for the public endpoints and the ones that take a bearer token.
+    ///
+    Any,
+
+    ///
+    /// Only the given origins can call the API.
+    ///
+    List(Vec<String>),
+}
+
+impl AllowedOrigins {
+    ///
+    /// Gets the allowed origins from `CORS_ALLOWED_ORIGINS`, which is only read once,
+    /// only allowing the UI when it isn't set.
+    ///
+    pub fn from_env() -> &'static AllowedOrigins {
+        static ORIGINS: OnceLock<AllowedOrigins> = OnceLock::new();
+
+        ORIGINS.get_or_init(|| match env::var(CORS_ORIGINS_ENV_VAR) {
+            Err(_) => AllowedOrigins::List(vec![DEFAULT_ORIGIN.to_owned()]),
+            Ok(origins) => AllowedOrigins::parse(&origins),
+        })
+    }
+
+    ///
+    /// Parses a list of origins separated by commas, where `*` allows any origin.
+    ///
+    pub fn parse(origins: &str) -> AllowedOrigins {
+        let origins = origins
+            .split(',')
+            .map(|origin| origin.trim().trim_end_matches('/'))
+            .filter(|origin| !origin.is_empty())
+            .map(str::to_owned)
+            .collect::<Vec<String>>();
+
+        return match origins.iter().any(|origin| origin == "*") {
+            true => AllowedOrigins::Any,
+            false => AllowedOrigins::List(origins),
+        };
+    }
+
+    ///
+    /// The value of the `Access-Control-Allow-Origin` header for a request from
+    /// `origin`, or `None` when the origin isn't allowed.
+    ///
+    fn allow_origin(&self, origin: Option<&str>) -> Option<String> {
+        return match self {
+            AllowedOrigins::Any => Some("*".to_owned()),
+            AllowedOrigins::List(origins) => origin
+                .filter(|origin| origins.iter().any(|allowed| allowed == origin))
+                .map(str::to_owned),
+        };
+    }
+}
+
+///
+/// Adds the CORS headers to a response, so that browsers let apps on the
+/// allowed origins read it.
+///
+pub fn add_cors_headers(
+    origins: &AllowedOrigins,
+    request_headers: &HeaderMap,
+    response_headers: &mut HeaderMap,
+) {
+    let origin = request_headers
+        .get(ORIGIN)
+        .and_then(|origin| origin.to_str().ok());
+
+    // The response depends on the origin unless every origin is allowed
+    if let AllowedOrigins::List(_) = origins {
+        response_headers.append(VARY, HeaderValue::from_static("Origin"));
+    }
+
+    let allow_origin = origins
+        .allow_origin(origin)
+        .and_then(|origin| HeaderValue::from_str(&origin).ok());
+    if let Some(allow_origin) = allow_origin {
+        response_headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+        response_headers.insert(
+            ACCESS_CONTROL_EXPOSE_HEADERS,
+            HeaderValue::from_static(EXPOSED_HEADERS),
+        );
+    }
+}
+
+///
+/// Builds the response to an `OPTIONS` request, which browsers send before
+/// requests that aren't simple, e.g. those with an `Authorization` header.
+///
+pub fn preflight(methods: &[Method]) -> Result<Response<Body>, Error> {
+    let allow = allow_header(methods);
+
+    Ok(Response::builder()
+        .status(204)
+        .header(ALLOW, &allow)
+        .header(ACCESS_CONTROL_ALLOW_METHODS, &allow)
+        .header(ACCESS_CONTROL_ALLOW_HEADERS, ALLOWED_HEADERS)
+        .header(ACCESS_CONTROL_MAX_AGE, PREFLIGHT_MAX_AGE)
+        .body(Body::Empty)?)
+}
+
+///
+/// The value of the `Allow` header for the given methods, e.g. `GET, HEAD, OPTIONS`.
+///
+pub fn allow_header(methods: &[Method]) -> String {
+    methods
+        .iter()
+        .map(Method::as_str)
+        .collect::<Vec<&str>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cors_headers(origins: &AllowedOrigins, origin: &str) -> HeaderMap {
+        let mut request_headers = HeaderMap::new();
+        request_headers.insert(ORIGIN, HeaderValue::from_str(origin).unwrap());
+
+        let mut response_headers = HeaderMap::new();
+        add_cors_headers(origins, &request_headers, &mut response_headers);
+        response_headers
+    }
+
+    #[test]
+    fn parse_allows_any_origin_with_a_wildcard() {
+        assert_eq!(AllowedOrigins::parse("*"), AllowedOrigins::Any);
+        assert_eq!(
+            AllowedOrigins::parse("https://a.example, *"),
+            AllowedOrigins::Any
+        );
+    }
+
+    #[test]
+    fn parse_trims_origins() {
+        assert_eq!(
+            AllowedOrigins::parse(" https://a.example/ ,,http://localhost:5173"),
+            AllowedOrigins::List(vec![
+                "https://a.example".to_owned(),
+                "http://localhost:5173".to_owned()
+            ])
+        );
+    }
+
+    #[test]
+    fn listed_origins_are_echoed() {
+        let origins = AllowedOrigins::parse(DEFAULT_ORIGIN);
+
+        let headers = cors_headers(&origins, DEFAULT_ORIGIN);
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_ORIGIN], DEFAULT_ORIGIN);
+        assert_eq!(headers[VARY], "Origin");
+
+        let headers = cors_headers(&origins, "https://evil.example");
+        assert!(!headers.contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+        assert_eq!(headers[VARY], "Origin");
+    }
+
+    #[test]
+    fn any_origin_is_allowed_with_a_wildcard() {
+        let headers = cors_headers(&AllowedOrigins::Any, "https://a.example");
+
+        assert_eq!(headers[ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+        assert!(!headers.contains_key(VARY));
+    }
+}
@@ -0,0 +1,232 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use quoted_api_models::page::PagedResponse;
+use sea_orm::{DatabaseConnection, EntityTrait, PaginatorTrait, QuerySelect, Select};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{api_response::ErrorResult, pipeline::db_error};
+
+///
+/// Applies the page requested by the client to a query.
+///
+pub trait Paginate: QuerySelect {
+    ///
+    /// Limits the query to the rows on the given page.
+    /// One more row than the `limit` is fetched so that `into_page` can tell
+    /// whether there are more pages available.
+    ///
+    fn paginate(self, page: u64, limit: u64) -> Self {
+        self.limit(limit + 1).offset(limit * (page - 1))
+    }
+}
+
+impl<T> Paginate for T where T: QuerySelect {}
+
+///
+/// Builds a `PagedResponse` from the rows fetched by a query that had `paginate` applied,
+/// dropping the extra row that was used to work out `has_more`.
+///
+pub fn into_page<T>(page: u64, limit: u64, mut rows: Vec<T>) -> PagedResponse<T>
+where
+    T: Serialize,
+{
+    let has_more = rows.len() > limit as usize;
+    rows.truncate(limit as usize);
+
+    PagedResponse::new(page, limit, rows, has_more)
+}
+
+///
+/// Builds a `PagedResponse` in the same way as `into_page`, setting `next_cursor`
+/// to the sort keys of the last row on the page, as given by `cursor_key`.
+///
+/// The rows must be in the order given by the sort keys.
+///
+pub fn into_keyset_page<T, K, F>(
+    page: u64,
+    limit: u64,
+    rows: Vec<T>,
+    cursor_key: F,
+) -> PagedResponse<T>
+where
+    T: Serialize,
+    K: Serialize,
+    F: Fn(&T) -> K,
+{
+    let page = into_page(page, limit, rows);
+
+    let next_cursor = match page.has_more {
+        false => None,
+        true => page.data.last().map(|row| encode_cursor(&cursor_key(row))),
+    };
+
+    page.with_next_cursor(next_cursor)
+}
+
+///
+/// Decodes the cursor sent by the client into the sort keys of the last row
+/// of the previous page. The next page starts after the row with these keys.
+///
+/// # Errors
+///
+/// When the cursor wasn't created by `into_keyset_page` with the same type of keys,
+/// or when a page other than the first was also requested.
+///
+pub fn decode_cursor<K>(cursor: &Option<String>, page: u64) -> Result<Option<K>, ErrorResult>
+where
+    K: DeserializeOwned,
+{
+    let cursor = match cursor {
+        None => return Ok(None),
+        Some(cursor) => cursor,
+    };
+
+    if page != 1 {
+        return Err(
+            ErrorResult::bad_request("A cursor cannot be used with a page").with_field("cursor"),
+        );
+    }
+
+    let key = URL_SAFE_NO_PAD
+        .decode(cursor)
+        .ok()
+        .and_then(|json| serde_json::from_slice::<K>(&json).ok());
+
+    return match key {
+        None => Err(ErrorResult::bad_request("Invalid cursor").with_field("cursor")),
+        Some(key) => Ok(Some(key)),
+    };
+}
+
+///
+/// Counts the rows matched by the query when the client asked for the total,
+/// to be set on the page with `PagedResponse::with_total`.
+///
+/// The query should apply the same filters as the query for the page, but
+/// without the paging, sorting or cursor.
+///
+pub async fn count_total<E>(
+    db: &DatabaseConnection,
+    query: Select<E>,
+    include_total: bool,
+) -> Result<Option<u64>, ErrorResult>
+where
+    E: EntityTrait,
+    E::Model: Sync,
+{
+    if !include_total {
+        return Ok(None);
+    }
+
+    return match query.count(db).await {
+        Err(e) => Err(db_error(e, "Error counting results")),
+        Ok(total) => Ok(Some(total)),
+    };
+}
+
+///
+/// Checks that the client didn't send a cursor to an endpoint that only supports pages.
+///
+pub fn reject_cursor(cursor: &Option<String>) -> Result<(), ErrorResult> {
+    return match cursor {
+        None => Ok(()),
+        Some(_) => Err(
+            ErrorResult::bad_request("Cursors are not supported by this endpoint")
+                .with_field("cursor"),
+        ),
+    };
+}
+
+fn encode_cursor<K>(key: &K) -> String
+where
+    K: Serialize,
+{
+    URL_SAFE_NO_PAD.encode(serde_json::to_vec(key).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+
+    fn cursor_error(result: Result<Option<(String, i32)>, ErrorResult>) -> String {
+        let error = result.err().unwrap();
+        assert_eq!(error.body.code, ErrorCode::BadRequest);
+        assert_eq!(error.body.errors[0].field, "cursor");
+        error.body.message.unwrap()
+    }
+
+    #[test]
+    fn cursors_round_trip() {
+        let key = ("Futurama".to_owned(), 42);
+
+        let decoded = decode_cursor::<(String, i32)>(&Some(encode_cursor(&key)), 1).unwrap();
+
+        assert_eq!(decoded, Some(key));
+    }
+
+    #[test]
+    fn no_cursor_decodes_to_none() {
+        assert_eq!(decode_cursor::<(String, i32)>(&None, 3).unwrap(), None);
+    }
+
+    #[test]
+    fn cursors_cannot_be_used_with_a_page() {
+        let cursor = encode_cursor(&("Futurama".to_owned(), 42));
+
+        let message = cursor_error(decode_cursor(&Some(cursor), 2));
+
+        assert_eq!(message, "A cursor cannot be used with a page");
+    }
+
+    #[test]
+    fn tampered_cursors_are_rejected() {
+        let mut cursor = encode_cursor(&("Futurama".to_owned(), 42));
+        cursor.insert(0, '!');
+
+        assert_eq!(
+            cursor_error(decode_cursor(&Some(cursor), 1)),
+            "Invalid cursor"
+        );
+    }
+
+    #[test]
+    fn cursors_for_other_keys_are_rejected() {
+        let cursor = encode_cursor(&42);
+
+        assert_eq!(
+            cursor_error(decode_cursor(&Some(cursor), 1)),
+            "Invalid cursor"
+        );
+        assert_eq!(
+            cursor_error(decode_cursor(&Some("not a cursor".to_owned()), 1)),
+            "Invalid cursor"
+        );
+    }
+
+    #[test]
+    fn pages_drop_the_extra_row() {
+        let page = into_page(1, 2, vec![1, 2, 3]);
+        assert_eq!(page.data, vec![1, 2]);
+        assert!(page.has_more);
+
+        let page = into_page(1, 2, vec![1, 2]);
+        assert_eq!(page.data, vec![1, 2]);
+        assert!(!page.has_more);
+    }
+
+    #[test]
+    fn keyset_pages_point_at_the_last_row() {
+        let page = into_keyset_page(1, 2, vec![10, 20, 30], |row| *row);
+        let cursor = page.next_cursor.clone();
+        assert_eq!(decode_cursor::<i32>(&cursor, 1).unwrap(), Some(20));
+
+        let page = into_keyset_page(1, 2, vec![10, 20], |row| *row);
+        assert_eq!(page.next_cursor, None);
+    }
+
+    #[test]
+    fn cursors_are_rejected_by_paged_endpoints() {
+        assert!(reject_cursor(&None).is_ok());
+        assert!(reject_cursor(&Some("abc".to_owned())).is_err());
+    }
+}
@@ -0,0 +1,499 @@
+use std::{any::type_name, collections::HashMap, env, sync::OnceLock};
+
+use chrono::{Duration, Utc};
+use http::HeaderMap;
+use quoted_db_entity::rate_limit;
+use rand::Rng;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, DbBackend, EntityTrait, FromQueryResult, QueryFilter,
+    Statement,
+};
+use tracing::{error, warn};
+
+use crate::{
+    api_response::ErrorResult,
+    auth::{bearer_token, find_token_user},
+    error::ErrorCode,
+};
+
+///
+/// The env var that turns rate limiting off when it's `false`, e.g. for local development.
+///
+const RATE_LIMITING_ENV_VAR: &str = "RATE_LIMITING";
+
+///
+/// The prefix of the env vars that override the limit of an endpoint, e.g.
+/// `RATE_LIMIT_SEARCH_QUOTES=20/40` for a burst of 20 requests and 40 requests a minute.
+///
+const ENDPOINT_LIMIT_ENV_VAR_PREFIX: &str = "RATE_LIMIT_";
+
+///
+/// Buckets that haven't been used for this long are removed. Every bucket refills
+/// well within this time, so removing it is the same as leaving it full.
+///
+const STALE_BUCKET_HOURS: i64 = 24;
+
+///
+/// How often, out of every this many requests, stale buckets are removed.
+///
+const CLEAN_UP_EVERY: u32 = 100;
+
+///
+/// How many requests a client can make to an endpoint.
+///
+/// Each client has a bucket of tokens for each endpoint, which starts with `burst`
+/// tokens and refills at `per_minute` tokens a minute, up to `burst`. Each request
+/// takes a token, and requests are rejected while the bucket is empty.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimit {
+    ///
+    /// Requests aren't limited.
+    ///
+    Unlimited,
+
+    ///
+    /// Requests are limited with a token bucket.
+    ///
+    Bucket { burst: u32, per_minute: u32 },
+}
+
+impl RateLimit {
+    ///
+    /// The limit for most endpoints.
+    ///
+    pub const DEFAULT: RateLimit = RateLimit::Bucket {
+        burst: 60,
+        per_minute: 60,
+    };
+
+    ///
+    /// The limit for endpoints with expensive queries, e.g. random quotes and search.
+    ///
+    pub const EXPENSIVE: RateLimit = RateLimit::Bucket {
+        burst: 10,
+        per_minute: 20,
+    };
+
+    ///
+    /// The limit for logging in, which slows down guessing passwords.
+    ///
+    pub const LOGIN: RateLimit = RateLimit::Bucket {
+        burst: 5,
+        per_minute: 5,
+    };
+
+    ///
+    /// Parses a limit in the form `burst/per_minute`, e.g. `10/20`.
+    /// Both must be more than zero.
+    ///
+    fn parse(value: &str) -> Option<RateLimit> {
+        let (burst, per_minute) = value.split_once('/')?;
+        let burst = burst
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|burst| *burst > 0)?;
+        let per_minute = per_minute
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|per_minute| *per_minute > 0)?;
+
+        Some(RateLimit::Bucket { burst, per_minute })
+    }
+}
+
+///
+/// The state of a bucket after trying to take a token from it.
+///
+#[derive(Debug, FromQueryResult)]
+struct Bucket {
+    ///
+    /// Whether a token was taken from the bucket.
+    ///
+    taken: bool,
+
+    ///
+    /// The tokens in the bucket when it was last updated.
+    ///
+    tokens: f64,
+
+    ///
+    /// The number of seconds since the bucket was last updated.
+    ///
+    elapsed_secs: f64,
+}
+
+impl Bucket {
+    ///
+    /// The tokens in the bucket now, after refilling it for the time since it was
+    /// last updated, up to `burst`.
+    ///
+    fn available(&self, burst: f64, refill_per_second: f64) -> f64 {
+        (self.tokens + self.elapsed_secs * refill_per_second).min(burst)
+    }
+}
+
+///
+/// Takes a token from the client's bucket for the endpoint `E`.
+///
+/// Clients that send a valid bearer token are limited as that user, and other
+/// clients by their IP address.
+///
+/// The `limit` of the endpoint can be overridden with `RATE_LIMIT_<ENDPOINT>`.
+///
+/// The request is allowed when the buckets can't be read, e.g. when the database
+/// is unavailable, so that the limiter can't take the API down.
+///
+/// # Errors
+///
+/// When the bucket is empty, in which case the `Retry-After` header says how many
+/// seconds until a token is available.
+///
+pub async fn check_rate_limit<E>(
+    headers: &HeaderMap,
+    db: &DatabaseConnection,
+    limit: RateLimit,
+) -> Result<(), ErrorResult> {
+    let (burst, per_minute) = match endpoint_limit::<E>(limit) {
+        RateLimit::Unlimited => return Ok(()),
+        RateLimit::Bucket { burst, per_minute } => (burst as f64, per_minute as f64),
+    };
+    if env::var(RATE_LIMITING_ENV_VAR).is_ok_and(|enabled| enabled.to_lowercase() == "false") {
+        return Ok(());
+    }
+
+    let key = format!("{}:{}", endpoint_name::<E>(), client_key(headers, db).await);
+    let refill_per_second = per_minute / 60.0;
+
+    if rand::thread_rng().gen_ratio(1, CLEAN_UP_EVERY) {
+        remove_stale_buckets(db).await;
+    }
+
+    let bucket = match Bucket::find_by_statement(take_token(&key, burst, refill_per_second))
+        .one(db)
+        .await
+    {
+        Err(e) => {
+            error!(error = %e, "Error checking rate limit");
+            return Ok(());
+        }
+        Ok(None) => return Ok(()),
+        Ok(Some(bucket)) => bucket,
+    };
+
+    if bucket.taken {
+        return Ok(());
+    }
+
+    let retry_after = retry_after(
+        bucket.available(burst, refill_per_second),
+        refill_per_second,
+    );
+    warn!(key, retry_after, "Rate limited");
+
+    return Err(
+        ErrorResult::new(ErrorCode::RateLimited, "Too many requests")
+            .with_header("Retry-After", &retry_after.to_string()),
+    );
+}
+
+///
+/// The number of whole seconds until a bucket with `available` tokens has a token,
+/// which is at least one.
+///
+fn retry_after(available: f64, refill_per_second: f64) -> u64 {
+    ((1.0 - available) / refill_per_second).ceil().max(1.0) as u64
+}
+
+///
+/// Builds the statement that refills the bucket for the time since it was last
+/// used and takes a token from it, if there is one, in a single statement so that
+/// concurrent requests can't take the same token.
+///
+/// The bucket is only updated when a token is taken, so `taken` is whether it was
+/// updated by this statement. `Bucket::available` refills the bucket in the same
+/// way to work out when a token will be available.
+///
+fn take_token(key: &str, burst: f64, refill_per_second: f64) -> Statement {
+    let refilled = "LEAST($2, rate_limit.tokens \
+        + EXTRACT(EPOCH FROM now() - rate_limit.updated_at)::float8 * $3)";
+
+    let sql = format!(
+        r#"INSERT INTO rate_limit (key, tokens, updated_at)
+        VALUES ($1, $2 - 1, now())
+        ON CONFLICT (key) DO UPDATE SET
+            tokens = CASE WHEN {refilled} >= 1 THEN {refilled} - 1 ELSE rate_limit.tokens END,
+            updated_at = CASE WHEN {refilled} >= 1 THEN now() ELSE rate_limit.updated_at END
+        RETURNING
+            updated_at = now() AS taken,
+            tokens,
+            EXTRACT(EPOCH FROM now() - updated_at)::float8 AS elapsed_secs"#
+    );
+
+    Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        sql,
+        [key.into(), burst.into(), refill_per_second.into()],
+    )
+}
+
+///
+/// Identifies the client, as the user when there's a valid bearer token,
+/// otherwise by IP address.
+///
+async fn client_key(headers: &HeaderMap, db: &DatabaseConnection) -> String {
+    if let Some(token) = bearer_token(headers) {
+        match find_token_user(db, token).await {
+            Err(e) => error!(error = %e, "Error checking token for rate limit"),
+            Ok(None) => {}
+            Ok(Some(user)) => return format!("user:{}", user.id),
+        }
+    }
+
+    format!("ip:{}", client_ip(headers))
+}
+
+///
+/// Gets the IP address of the client from the `x-real-ip` header set by Vercel.
+///
+/// When it's missing, e.g. when the local server is behind a proxy, the last address
+/// in `x-forwarded-for` is used, as that's the one added by the proxy. The earlier
+/// addresses are sent by the client, so can't be trusted. Requests without either
+/// header share a single bucket.
+///
+fn client_ip(headers: &HeaderMap) -> &str {
+    let real_ip = headers.get("x-real-ip").and_then(|ip| ip.to_str().ok());
+    let forwarded_for = headers
+        .get("x-forwarded-for")
+        .and_then(|ips| ips.to_str().ok())
+        .and_then(|ips| ips.rsplit(',').next());
+
+    real_ip
+        .or(forwarded_for)
+        .map(str::trim)
+        .filter(|ip| !ip.is_empty())
+        .unwrap_or("unknown")
+}
+
+///
+/// The limit of the endpoint `E`, which is the one in `RATE_LIMIT_<ENDPOINT>` when
+/// it's set, e.g. `RATE_LIMIT_GET_RANDOM_QUOTE`, otherwise the endpoint's own `limit`.
+///
+fn endpoint_limit<E>(limit: RateLimit) -> RateLimit {
+    static LIMITS: OnceLock<HashMap<String, RateLimit>> = OnceLock::new();
+
+    LIMITS
+        .get_or_init(|| parse_endpoint_limits(env::vars()))
+        .get(&env_var_suffix(endpoint_name::<E>()))
+        .copied()
+        .unwrap_or(limit)
+}
+
+///
+/// Parses the limits of the endpoints from the `RATE_LIMIT_<ENDPOINT>` env vars,
+/// ignoring those that aren't valid.
+///
+fn parse_endpoint_limits<I>(vars: I) -> HashMap<String, RateLimit>
+where
+    I: Iterator<Item = (String, String)>,
+{
+    vars.filter_map(|(name, value)| {
+        let endpoint = name.strip_prefix(ENDPOINT_LIMIT_ENV_VAR_PREFIX)?;
+        match RateLimit::parse(&value) {
+            None => {
+                warn!(name, value, "Invalid rate limit, using the default");
+                None
+            }
+            Some(limit) => Some((endpoint.to_owned(), limit)),
+        }
+    })
+    .collect()
+}
+
+///
+/// Converts the name of an endpoint into the suffix of its env var,
+/// e.g. `GetRandomQuote` into `GET_RANDOM_QUOTE`.
+///
+fn env_var_suffix(endpoint_name: &str) -> String {
+    let mut suffix = String::new();
+    for (i, c) in endpoint_name.chars().enumerate() {
+        if i > 0 && c.is_ascii_uppercase() {
+            suffix.push('_');
+        }
+        suffix.push(c.to_ascii_uppercase());
+    }
+    suffix
+}
+
+///
+/// The name of the endpoint in the keys of its buckets, e.g. `GetRandomQuote`.
+///
+fn endpoint_name<E>() -> &'static str {
+    let name = type_name::<E>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+async fn remove_stale_buckets(db: &DatabaseConnection) {
+    let stale = Utc::now().fixed_offset() - Duration::hours(STALE_BUCKET_HOURS);
+
+    let result = rate_limit::Entity::delete_many()
+        .filter(rate_limit::Column::UpdatedAt.lt(stale))
+        .exec(db)
+        .await;
+    if let Err(e) = result {
+        error!(error = %e, "Error removing stale rate limit buckets");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::HeaderValue;
+
+    const ONE_PER_SECOND: f64 = 1.0;
+    const ONE_EVERY_THREE_SECONDS: f64 = 20.0 / 60.0;
+
+    fn bucket(tokens: f64, elapsed_secs: f64) -> Bucket {
+        Bucket {
+            taken: false,
+            tokens,
+            elapsed_secs,
+        }
+    }
+
+    #[test]
+    fn buckets_refill_over_time() {
+        assert_eq!(bucket(0.0, 0.0).available(10.0, ONE_PER_SECOND), 0.0);
+        assert_eq!(bucket(0.0, 2.5).available(10.0, ONE_PER_SECOND), 2.5);
+        assert_eq!(
+            bucket(0.5, 3.0).available(10.0, ONE_EVERY_THREE_SECONDS),
+            1.5
+        );
+    }
+
+    #[test]
+    fn buckets_refill_up_to_the_burst() {
+        assert_eq!(bucket(4.0, 60.0).available(5.0, ONE_PER_SECOND), 5.0);
+        assert_eq!(
+            bucket(0.0, 3600.0).available(10.0, ONE_EVERY_THREE_SECONDS),
+            10.0
+        );
+    }
+
+    #[test]
+    fn retry_after_is_the_time_until_a_whole_token() {
+        assert_eq!(retry_after(0.0, ONE_PER_SECOND), 1);
+        assert_eq!(retry_after(0.0, ONE_EVERY_THREE_SECONDS), 3);
+        assert_eq!(retry_after(0.5, ONE_EVERY_THREE_SECONDS), 2);
+        assert_eq!(retry_after(0.0, 5.0 / 60.0), 12);
+    }
+
+    #[test]
+    fn retry_after_is_at_least_one_second() {
+        assert_eq!(retry_after(0.99, ONE_PER_SECOND), 1);
+        assert_eq!(retry_after(0.0, 100.0), 1);
+    }
+
+    #[test]
+    fn limits_refill_at_their_rate() {
+        let RateLimit::Bucket { burst, per_minute } = RateLimit::EXPENSIVE else {
+            panic!("Expensive endpoints should be limited");
+        };
+        let refill_per_second = per_minute as f64 / 60.0;
+
+        // An empty bucket gets a token back after 3 seconds, and is full after 30
+        assert_eq!(retry_after(0.0, refill_per_second), 3);
+        assert_eq!(
+            bucket(0.0, 30.0).available(burst as f64, refill_per_second),
+            burst as f64
+        );
+    }
+
+    #[test]
+    fn clients_are_identified_by_the_real_ip() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(client_ip(&headers), "unknown");
+
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("203.0.113.7, 10.0.0.1"),
+        );
+        headers.insert("x-real-ip", HeaderValue::from_static("198.51.100.2"));
+        assert_eq!(client_ip(&headers), "198.51.100.2");
+    }
+
+    #[test]
+    fn clients_are_identified_by_the_last_forwarded_ip_without_a_real_ip() {
+        let mut headers = HeaderMap::new();
+
+        // The first address is set by the client, and the last by the proxy
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("203.0.113.7, 10.0.0.1 "),
+        );
+        assert_eq!(client_ip(&headers), "10.0.0.1");
+
+        headers.insert("x-forwarded-for", HeaderValue::from_static("203.0.113.7"));
+        assert_eq!(client_ip(&headers), "203.0.113.7");
+    }
+
+    #[test]
+    fn limits_are_parsed_from_burst_and_per_minute() {
+        assert_eq!(
+            RateLimit::parse("10/20"),
+            Some(RateLimit::Bucket {
+                burst: 10,
+                per_minute: 20
+            })
+        );
+        assert_eq!(
+            RateLimit::parse(" 5 / 1 "),
+            Some(RateLimit::Bucket {
+                burst: 5,
+                per_minute: 1
+            })
+        );
+        assert_eq!(RateLimit::parse("10"), None);
+        assert_eq!(RateLimit::parse("0/20"), None);
+        assert_eq!(RateLimit::parse("10/0"), None);
+        assert_eq!(RateLimit::parse("ten/20"), None);
+    }
+
+    #[test]
+    fn endpoint_limits_are_read_from_their_env_vars() {
+        let vars = [
+            ("RATE_LIMIT_SEARCH_QUOTES", "20/40"),
+            ("RATE_LIMIT_LOGIN", "none"),
+            ("RATE_LIMITING", "false"),
+            ("DATABASE_URL", "postgres://"),
+        ]
+        .map(|(name, value)| (name.to_owned(), value.to_owned()));
+
+        let limits = parse_endpoint_limits(vars.into_iter());
+
+        assert_eq!(limits.len(), 1);
+        assert_eq!(
+            limits["SEARCH_QUOTES"],
+            RateLimit::Bucket {
+                burst: 20,
+                per_minute: 40
+            }
+        );
+    }
+
+    #[test]
+    fn endpoint_env_vars_are_named_after_the_endpoint() {
+        assert_eq!(env_var_suffix("GetRandomQuote"), "GET_RANDOM_QUOTE");
+        assert_eq!(env_var_suffix("Login"), "LOGIN");
+    }
+
+    #[test]
+    fn buckets_are_named_after_the_endpoint() {
+        assert_eq!(
+            endpoint_name::<crate::handlers::random_quote::GetRandomQuote>(),
+            "GetRandomQuote"
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use axum::{
+    body::{to_bytes, Body as AxumBody},
+    extract::{Path, Request as AxumRequest, State},
+    http::{Method, StatusCode, Uri},
+    response::{IntoResponse, Response as AxumResponse},
+    routing::{any, MethodRouter},
+    Router,
+};
+use sea_orm::DatabaseConnection;
+use tracing::{error, warn};
+use vercel_runtime::{Body, Error, Request, Response};
+
+use crate::{
+    handlers::{
+        characters::GetCharactersInShow, create_quote::CreateQuote, daily_quote::GetDailyQuote,
+        delete_quote::DeleteQuote, episodes::GetEpisodesInSeason, health::GetHealth, login::Login,
+        openapi::GetOpenApi, quote::GetQuote, quotes::GetQuotes, random_quote::GetRandomQuote,
+        search_quotes::SearchQuotes, seasons::GetSeasonsInShow, shows::GetShows, stats::GetStats,
+        update_quote::UpdateQuote,
+    },
+    pipeline::{handle, Endpoint},
+};
+
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+type PathParams = HashMap<String, String>;
+
+///
+/// A path served by the local server, along with the HTTP methods it supports.
+///
+pub struct Route {
+    ///
+    /// The path, in axum's syntax, e.g. `/api/show/:show/seasons`.
+    ///
+    pub path: &'static str,
+
+    ///
+    /// The HTTP methods handled by the endpoint.
+    ///
+    pub methods: Vec<Method>,
+
+    handler: MethodRouter<DatabaseConnection>,
+}
+
+impl Route {
+    fn new<E>(path: &'static str) -> Route
+    where
+        E: Endpoint + 'static,
+    {
+        Route {
+            path,
+            methods: E::methods(),
+            handler: any(dispatch::<E>),
+        }
+    }
+}
+
+///
+/// Lists every path served by the API.
+///
+pub fn routes() -> Vec<Route> {
+    vec![
+        Route::new::<Login>("/api/auth/login"),
+        Route::new::<GetShows>("/api/shows"),
+        Route::new::<GetSeasonsInShow>("/api/show/:show/seasons"),
+        Route::new::<GetCharactersInShow>("/api/show/:show/characters"),
+        Route::new::<GetEpisodesInSeason>("/api/show/:show/season/:season/episodes"),
+        Route::new::<(GetQuotes, CreateQuote)>("/api/quotes"),
+        Route::new::<SearchQuotes>("/api/quotes/search"),
+        Route::new::<GetRandomQuote>("/api/quote/random"),
+        Route::new::<GetDailyQuote>("/api/quote/daily"),
+        Route::new::<(GetQuote, UpdateQuote, DeleteQuote)>("/api/quote/:id"),
+        Route::new::<GetStats>("/api/stats"),
+        Route::new::<GetOpenApi>("/api/openapi.json"),
+        Route::new::<GetHealth>("/api/health"),
+    ]
+}
+
+///
+/// Builds a router that serves every API endpoint, sharing the given database
+/// connection pool between requests.
+///
+pub fn router(db: DatabaseConnection) -> Router {
+    routes()
+        .into_iter()
+        .fold(Router::new(), |router, route| {
+            router.route(route.path, route.handler)
+        })
+        .with_state(db)
+}
+
+/// Converts the incoming request into the shape the Vercel runtime would pass
+/// to a handler, runs the handler, and converts the result back.
+async fn dispatch<E>(
+    State(db): State<DatabaseConnection>,
+    path_params: Option<Path<PathParams>>,
+    req: AxumRequest,
+) -> AxumResponse
+where
+    E: Endpoint,
+{
+    let path_params = path_params.map(|Path(p)| p).unwrap_or_default();
+
+    let req = match to_vercel_request(req, path_params).await {
+        Ok(req) => req,
+        Err(e) => {
+            warn!(error = %e, "Error reading request");
+            return StatusCode::BAD_REQUEST.into_response();
+        }
+    };
+
+    match handle::<E>(req, db).await {
+        Ok(res) => to_axum_response(res),
+        Err(e) => {
+            error!(error = %e, "Handler returned error");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+async fn to_vercel_request(req: AxumRequest, path_params: PathParams) -> Result<Request, Error> {
+    let (mut parts, body) = req.into_parts();
+    let bytes = to_bytes(body, MAX_BODY_BYTES).await?;
+
+    parts.uri = with_path_params(&parts.uri, path_params)?;
+
+    let body = if bytes.is_empty() {
+        Body::Empty
+    } else {
+        match String::from_utf8(bytes.to_vec()) {
+            Ok(text) => Body::Text(text),
+            Err(e) => Body::Binary(e.into_bytes()),
+        }
+    };
+
+    Ok(Request::from_parts(parts, body))
+}
+
+fn to_axum_response(res: Response<Body>) -> AxumResponse {
+    res.map(|body| match body {
+        Body::Empty => AxumBody::empty(),
+        Body::Text(text) => AxumBody::from(text),
+        Body::Binary(bytes) => AxumBody::from(bytes),
+    })
+}
+
+/// Vercel passes dynamic path segments (e.g. `[show]` in `api/show/[show]/seasons.rs`)
+/// to the handler as query parameters, so the same is done here.
+fn with_path_params(uri: &Uri, path_params: PathParams) -> Result<Uri, Error> {
+    if path_params.is_empty() {
+        return Ok(uri.clone());
+    }
+
+    let params = serde_urlencoded::to_string(path_params)?;
+    let query = match uri.query() {
+        Some(query) if !query.is_empty() => format!("{}&{}", query, params),
+        _ => params,
+    };
+
+    Ok(Uri::builder()
+        .path_and_query(format!("{}?{}", uri.path(), query))
+        .build()?)
+}
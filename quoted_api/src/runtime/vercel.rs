@@ -0,0 +1,74 @@
+use quoted_db::{error::DBError, get_default_connection};
+use sea_orm::DatabaseConnection;
+use tracing::warn;
+use vercel_runtime::Error;
+
+use crate::{
+    api_response::{ErrorResult, VercelResponse},
+    error::ApiError,
+    pipeline::{handle, Endpoint},
+};
+
+///
+/// Runs the endpoint `E` as a Vercel serverless function.
+///
+pub async fn run<E>() -> Result<(), Error>
+where
+    E: Endpoint,
+{
+    vercel_runtime::run(|req| async {
+        let db = match connection_for::<E>(get_default_connection().await) {
+            Err(e) => return e.vercel(),
+            Ok(db) => db,
+        };
+        handle::<E>(req, db).await
+    })
+    .await
+}
+
+///
+/// Gets the connection pool for the endpoint `E`.
+///
+/// When the pool can't be created, endpoints that don't require it are given a
+/// disconnected connection, so that e.g. the health check can still report that
+/// the database can't be reached.
+///
+fn connection_for<E>(
+    db: Result<DatabaseConnection, DBError>,
+) -> Result<DatabaseConnection, ErrorResult>
+where
+    E: Endpoint,
+{
+    match db {
+        Ok(db) => Ok(db),
+        Err(e) if !E::requires_db() => {
+            warn!(error = ?e, "Error creating the connection pool");
+            Ok(DatabaseConnection::Disconnected)
+        }
+        Err(e) => Err(ErrorResult::from(ApiError::from(e))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::{health::GetHealth, shows::GetShows};
+
+    fn pool_error() -> Result<DatabaseConnection, DBError> {
+        Err(DBError::ConnectionParamRequired("DATABASE_URL".to_owned()))
+    }
+
+    #[test]
+    fn health_runs_without_a_pool() {
+        let db = connection_for::<GetHealth>(pool_error()).unwrap();
+
+        assert!(matches!(db, DatabaseConnection::Disconnected));
+    }
+
+    #[test]
+    fn other_endpoints_fail_without_a_pool() {
+        let error = connection_for::<GetShows>(pool_error()).err().unwrap();
+
+        assert_eq!(error.status_code, 500);
+    }
+}
@@ -1,11 +1,14 @@
 use std::env;
 
-use quoted_db::enable_query_logging;
+use crate::observability;
 
 pub fn setup() {
+    let mut directives = vec![];
     if let Ok(log) = env::var("QUERY_LOGGING") {
         if log.to_lowercase() == "true".to_owned() {
-            enable_query_logging();
+            directives.push("quoted_api::observability=debug");
         }
     }
+
+    observability::init(&directives);
 }
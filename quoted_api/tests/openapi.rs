@@ -0,0 +1,122 @@
+use std::{env, fs, path::Path};
+
+use http::Method;
+use quoted_api::{
+    openapi::{document, document_json},
+    runtime::local::routes,
+};
+use serde_json::Value;
+
+const OPENAPI_FILE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/openapi.json");
+
+///
+/// Fails when the models or paths have changed without `openapi.json` being updated.
+/// Run with `UPDATE_OPENAPI=1` to update it.
+///
+#[test]
+fn openapi_json_is_up_to_date() {
+    let generated = document_json();
+
+    if env::var("UPDATE_OPENAPI").is_ok() {
+        fs::write(OPENAPI_FILE, &generated).unwrap();
+    }
+
+    let saved = fs::read_to_string(Path::new(OPENAPI_FILE)).unwrap_or_default();
+    assert!(
+        saved == generated,
+        "openapi.json is out of date, run `UPDATE_OPENAPI=1 cargo test -p quoted_api --test openapi` to update it"
+    );
+}
+
+///
+/// Fails when a route is served without being described in the document, or is
+/// described with different methods.
+///
+#[test]
+fn every_route_is_documented() {
+    let document = document();
+
+    for route in routes() {
+        // e.g. /api/show/:show/seasons -> /api/show/{show}/seasons
+        let path = route
+            .path
+            .split('/')
+            .map(|segment| match segment.strip_prefix(':') {
+                Some(name) => format!("{{{}}}", name),
+                None => segment.to_owned(),
+            })
+            .collect::<Vec<String>>()
+            .join("/");
+
+        let item = document
+            .paths
+            .paths
+            .get(&path)
+            .unwrap_or_else(|| panic!("{} is not documented", path));
+
+        let mut documented = [
+            (Method::GET, item.get.is_some()),
+            (Method::POST, item.post.is_some()),
+            (Method::PUT, item.put.is_some()),
+            (Method::DELETE, item.delete.is_some()),
+            (Method::PATCH, item.patch.is_some()),
+        ]
+        .into_iter()
+        .filter(|(_, documented)| *documented)
+        .map(|(method, _)| method)
+        .collect::<Vec<Method>>();
+        documented.sort_by_key(|method| method.to_string());
+
+        let mut methods = route.methods;
+        methods.sort_by_key(|method| method.to_string());
+
+        assert_eq!(methods, documented, "{} has different methods", path);
+    }
+
+    assert_eq!(
+        document.paths.paths.len(),
+        routes().len(),
+        "Paths are documented that aren't served"
+    );
+}
+
+///
+/// Fails when the document refers to a schema that it doesn't define, e.g. an
+/// enum that's only used by parameters and wasn't added to the components.
+///
+#[test]
+fn every_schema_reference_resolves() {
+    let document: Value = serde_json::from_str(&document_json()).unwrap();
+    let schemas = &document["components"]["schemas"];
+
+    let mut references = vec![];
+    find_references(&document, &mut references);
+
+    for reference in references {
+        let name = reference.trim_start_matches("#/components/schemas/");
+        assert!(
+            schemas.get(name).is_some(),
+            "{} is referenced but not defined",
+            reference
+        );
+    }
+}
+
+fn find_references(value: &Value, references: &mut Vec<String>) {
+    match value {
+        Value::Object(fields) => {
+            for (name, value) in fields {
+                match value {
+                    Value::String(reference) if name == "$ref" => {
+                        references.push(reference.clone())
+                    }
+                    value => find_references(value, references),
+                }
+            }
+        }
+        Value::Array(items) => items
+            .iter()
+            .for_each(|item| find_references(item, references)),
+        _ => {}
+    }
+}
@@ -0,0 +1,151 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::validate::{Rules, Validate};
+
+///
+/// The maximum number of characters in an email address.
+///
+pub const MAX_EMAIL_LENGTH: usize = 254;
+
+///
+/// Defines the body of a request to log in.
+///
+pub type LoginRequest = LoginRequestBody;
+
+///
+/// Defines the response returned when a user has logged in.
+///
+pub type LoginResponse = LoginResponseBody;
+
+///
+/// Defines what a user is allowed to do.
+/// Roles are ordered, with each role being allowed to do everything the roles
+/// before it can do.
+///
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    ///
+    /// Can add and change quotes.
+    ///
+    Contributor,
+
+    ///
+    /// Can do anything, including deleting quotes.
+    ///
+    Admin,
+}
+
+///
+/// Defines the body of a request to log in.
+///
+/// Example request body:
+/// ```json
+/// { "email": "someone@example.com", "password": "..." }
+/// ```
+///
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct LoginRequestBody {
+    ///
+    /// The email address of the user logging in.
+    ///
+    pub email: String,
+
+    ///
+    /// The password of the user logging in.
+    ///
+    pub password: String,
+}
+
+///
+/// Defines the response returned when a user has logged in.
+///
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+pub struct LoginResponseBody {
+    ///
+    /// The token to be sent in the `Authorization` header of subsequent requests,
+    /// e.g. `Authorization: Bearer {token}`.
+    ///
+    pub token: String,
+
+    ///
+    /// When the token expires, as an RFC 3339 timestamp.
+    /// The user has to log in again after this.
+    ///
+    pub expires_at: String,
+
+    ///
+    /// The user that logged in.
+    ///
+    pub user: User,
+}
+
+///
+/// Defines a user of the API.
+///
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+pub struct User {
+    ///
+    /// The unique ID of the user.
+    ///
+    pub id: String,
+
+    ///
+    /// The name of the user.
+    ///
+    pub name: String,
+
+    ///
+    /// The email address of the user.
+    ///
+    pub email: String,
+
+    ///
+    /// What the user is allowed to do.
+    ///
+    pub role: Role,
+}
+
+// The password is left out so that it's never logged.
+impl fmt::Debug for LoginRequestBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoginRequestBody")
+            .field("email", &self.email)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Role {
+    ///
+    /// The name of the role, as stored against users.
+    ///
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Contributor => "contributor",
+            Role::Admin => "admin",
+        }
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "contributor" => Ok(Role::Contributor),
+            "admin" => Ok(Role::Admin),
+            _ => Err(format!("Unknown role {}", s)),
+        }
+    }
+}
+
+impl Validate for LoginRequestBody {
+    fn rules(&self, rules: &mut Rules) {
+        rules
+            .length("email", self.email.as_str(), 1, MAX_EMAIL_LENGTH)
+            .check("password", !self.password.is_empty(), "Is required");
+    }
+}
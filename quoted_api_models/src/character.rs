@@ -0,0 +1,64 @@
+use sea_orm::FromQueryResult;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    page::{PagedRequest, PagedResponse},
+    validate::{Rules, Validate, MAX_NAME_LENGTH},
+};
+use utoipa::{IntoParams, ToSchema};
+
+///
+/// Defines the request to fetch the characters in a given show.
+///
+pub type GetCharactersInShowRequest = PagedRequest<GetCharactersInShowRequestParams>;
+
+///
+/// Defines the response returned when fetching the characters in a given show.
+///
+pub type GetCharactersInShowResponse = PagedResponse<GetCharactersInShowResponseItem>;
+
+///
+/// The parameters supported when fetching the characters in a given show.
+/// These are in addition to the parameters provided by `PagedRequest`.
+///
+/// Example request URL:
+///      http://base-url/api/show/{show}/characters?name=fry
+///
+#[derive(Serialize, Deserialize, Debug, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GetCharactersInShowRequestParams {
+    ///
+    /// The name of the show to fetch characters for.
+    ///
+    pub show: String,
+
+    ///
+    /// Optional query parameter to search characters based on their name.
+    ///
+    pub name: Option<String>,
+}
+
+///
+/// The data that will be returned for a given character when fetching the
+/// characters in a given show.
+///
+#[derive(Serialize, Deserialize, FromQueryResult, Debug, Clone, ToSchema)]
+pub struct GetCharactersInShowResponseItem {
+    ///
+    /// The name of the character.
+    ///
+    pub name: String,
+
+    ///
+    /// The number of quotes in the show that the character is involved in.
+    ///
+    pub quote_count: i32,
+}
+
+impl Validate for GetCharactersInShowRequestParams {
+    fn rules(&self, rules: &mut Rules) {
+        rules
+            .length("show", self.show.as_str(), 1, MAX_NAME_LENGTH)
+            .length("name", self.name.as_deref(), 0, MAX_NAME_LENGTH);
+    }
+}
@@ -2,7 +2,11 @@ use sea_orm::FromQueryResult;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 
-use crate::page::{PagedRequest, PagedResponse};
+use crate::{
+    page::{PagedRequest, PagedResponse},
+    validate::{Rules, Validate, MAX_NAME_LENGTH},
+};
+use utoipa::{IntoParams, ToSchema};
 
 ///
 /// Defines a request to fetch episodes for a given show and season.
@@ -20,7 +24,8 @@ pub type GetEpisodesInSeasonResponse = PagedResponse<GetEpisodesInSeasonResponse
 /// that belong to a season for a given show.
 ///
 #[serde_as]
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct GetEpisodesInSeasonRequestParams {
     ///
     /// The name of the show to fetch episodes for.
@@ -38,23 +43,23 @@ pub struct GetEpisodesInSeasonRequestParams {
 /// Defines a single episode that will be returned when fetching the episodes
 /// within a given show and season.
 ///
-#[derive(Serialize, Deserialize, Debug, FromQueryResult, Clone)]
+#[derive(Serialize, Deserialize, Debug, FromQueryResult, Clone, ToSchema)]
 pub struct GetEpisodesInSeasonResponseItem {
     ///
     /// The number of the episode within the season
     ///
-    episode_no: i32,
+    pub episode_no: i32,
 
     ///
     /// The name of the episode within the season.
     /// Not all episodes will have a name.
     ///
-    episode_name: Option<String>,
+    pub episode_name: Option<String>,
 
     ///
     /// The number of quotes linked to this episode
     ///
-    quote_count: i32,
+    pub quote_count: i32,
 }
 
 impl Default for GetEpisodesInSeasonRequestParams {
@@ -65,3 +70,11 @@ impl Default for GetEpisodesInSeasonRequestParams {
         }
     }
 }
+
+impl Validate for GetEpisodesInSeasonRequestParams {
+    fn rules(&self, rules: &mut Rules) {
+        rules
+            .length("show", self.show.as_str(), 1, MAX_NAME_LENGTH)
+            .min("season", Some(self.season), 1);
+    }
+}
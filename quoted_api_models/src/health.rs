@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+///
+/// Defines the response returned when checking the health of the API.
+///
+pub type GetHealthResponse = GetHealthResponseBody;
+
+///
+/// Whether the API is able to serve requests.
+///
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    ///
+    /// The database can be reached and its schema is up to date.
+    ///
+    Ok,
+
+    ///
+    /// The database can't be reached, or has migrations that haven't been applied.
+    ///
+    Unavailable,
+}
+
+///
+/// Defines the response returned when checking the health of the API.
+///
+/// Example response body:
+/// ```json
+/// {
+///   "status": "ok",
+///   "version": "0.1.0",
+///   "commit": "2f1c0e9",
+///   "database": { "reachable": true, "latency_ms": 3, "error": null },
+///   "migrations": { "applied": 11, "pending": [], "unknown": [] }
+/// }
+/// ```
+///
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+pub struct GetHealthResponseBody {
+    ///
+    /// Whether the API is able to serve requests.
+    ///
+    pub status: HealthStatus,
+
+    ///
+    /// The version of the API that's deployed.
+    ///
+    pub version: String,
+
+    ///
+    /// The git commit that was deployed, when the deployment provides it.
+    ///
+    pub commit: Option<String>,
+
+    ///
+    /// Whether the database can be reached.
+    ///
+    pub database: DatabaseHealth,
+
+    ///
+    /// Whether the schema of the database is up to date.
+    /// This is `null` when the database can't be reached, or the migrations applied
+    /// to it can't be read.
+    ///
+    pub migrations: Option<MigrationHealth>,
+}
+
+///
+/// Whether the database can be reached.
+///
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+pub struct DatabaseHealth {
+    ///
+    /// Whether the database responded to a ping.
+    ///
+    pub reachable: bool,
+
+    ///
+    /// How many milliseconds the ping took, when the database responded.
+    ///
+    pub latency_ms: Option<u64>,
+
+    ///
+    /// A message saying that the database couldn't be reached, when it couldn't.
+    /// The details of the error are only logged, as they can include connection details.
+    ///
+    pub error: Option<String>,
+}
+
+///
+/// Compares the migrations applied to the database with the ones in the deployment.
+///
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+pub struct MigrationHealth {
+    ///
+    /// The number of migrations that have been applied to the database.
+    ///
+    pub applied: usize,
+
+    ///
+    /// The migrations in the deployment that haven't been applied to the database.
+    ///
+    pub pending: Vec<String>,
+
+    ///
+    /// The migrations applied to the database that aren't in the deployment,
+    /// e.g. because a newer version has already been deployed elsewhere.
+    ///
+    pub unknown: Vec<String>,
+}
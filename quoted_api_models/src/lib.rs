@@ -1,5 +1,10 @@
+pub mod auth;
+pub mod character;
 pub mod episode;
+pub mod health;
 pub mod page;
 pub mod quote;
 pub mod season;
 pub mod show;
+pub mod stats;
+pub mod validate;
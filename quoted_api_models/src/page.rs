@@ -1,10 +1,24 @@
 use serde::Serialize;
 use serde::{self, Deserialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::validate::{Rules, Validate};
+
+///
+/// The maximum number of items that can be requested on a single page.
+///
+pub const MAX_PAGE_LIMIT: u64 = 100;
+
+///
+/// The highest page number that can be requested.
+/// Pages beyond this should be fetched with a cursor instead.
+///
+pub const MAX_PAGE: u64 = 10_000;
 
 ///
 /// Defines the response from fetching a page of data.
 ///
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct PagedResponse<T>
 where
     T: Serialize,
@@ -25,6 +39,27 @@ where
     ///
     pub has_more: bool,
 
+    ///
+    /// An opaque value that can be sent as the `cursor` of the next request to
+    /// fetch the page after this one. Only set when `has_more` is true and the
+    /// endpoint supports cursors.
+    ///
+    pub next_cursor: Option<String>,
+
+    ///
+    /// The total number of items across every page.
+    /// Only included when the request set `include_total`.
+    ///
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+
+    ///
+    /// The total number of pages, based on `limit`.
+    /// Only included when the request set `include_total`.
+    ///
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_pages: Option<u64>,
+
     ///
     /// The data on the page.
     ///
@@ -34,7 +69,8 @@ where
 ///
 /// Defines a request to fetch a page of data.
 ///
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(default)]
 pub struct PagedRequest<T>
 where
@@ -42,23 +78,50 @@ where
 {
     ///
     /// The page number to be fetched.
-    /// Defaults to `1`.
+    /// Defaults to `1`, and can be at most `10000`.
     ///
     pub page: u64,
 
     ///
     /// The maximum number of items to include on the page.
-    /// Defaults to `10``.
+    /// Defaults to `10``, and can be at most `100`.
     ///
     pub limit: u64,
 
+    ///
+    /// The `next_cursor` returned with the previous page.
+    /// When given, the page starts after the last item of the previous page, and
+    /// `page` must be left as `1`. Unlike `page`, items are not skipped or repeated
+    /// when data is added between requests.
+    ///
+    pub cursor: Option<String>,
+
+    ///
+    /// Whether to include the `total` number of items and `total_pages` in the response.
+    /// Defaults to `false`, as it takes an extra query to count the items.
+    ///
+    pub include_total: bool,
+
     ///
     /// The request parameters specific to the item being searched.
+    /// These are documented by `T` itself, as `IntoParams` can't flatten them.
     ///
     #[serde(flatten)]
+    #[param(ignore)]
     pub query: T,
 }
 
+///
+/// The direction that items are sorted in.
+///
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
 impl<T> Default for PagedRequest<T>
 where
     T: Serialize,
@@ -68,11 +131,39 @@ where
         Self {
             page: 1,
             limit: 10,
+            cursor: Default::default(),
+            include_total: Default::default(),
             query: Default::default(),
         }
     }
 }
 
+impl<T> Validate for PagedRequest<T>
+where
+    T: Serialize,
+    T: Validate,
+{
+    fn rules(&self, rules: &mut Rules) {
+        page_rules(rules, self.page, self.limit, &self.cursor);
+        self.query.rules(rules);
+    }
+}
+
+///
+/// Declares the rules for the paging parameters of a request, for requests
+/// that include them without using `PagedRequest`.
+///
+pub fn page_rules(rules: &mut Rules, page: u64, limit: u64, cursor: &Option<String>) {
+    rules
+        .range("page", page, 1, MAX_PAGE)
+        .range("limit", limit, 1, MAX_PAGE_LIMIT)
+        .check(
+            "cursor",
+            cursor.is_none() || page == 1,
+            "A cursor cannot be used with a page",
+        );
+}
+
 impl<T> PagedResponse<T>
 where
     T: Serialize,
@@ -83,6 +174,20 @@ where
             limit,
             data,
             has_more,
+            next_cursor: None,
+            total: None,
+            total_pages: None,
         }
     }
+
+    pub fn with_next_cursor(mut self, next_cursor: Option<String>) -> Self {
+        self.next_cursor = next_cursor;
+        self
+    }
+
+    pub fn with_total(mut self, total: Option<u64>) -> Self {
+        self.total = total;
+        self.total_pages = total.map(|total| total.div_ceil(self.limit.max(1)));
+        self
+    }
 }
@@ -1,12 +1,80 @@
 use serde::{Deserialize, Serialize};
 
-use crate::page::{PagedRequest, PagedResponse};
+use std::collections::HashSet;
+
+use crate::{
+    page::{page_rules, PagedRequest, PagedResponse, SortDirection},
+    validate::{Rules, Validate, MAX_NAME_LENGTH},
+};
+use utoipa::{IntoParams, ToSchema};
 
 ///
-/// Defines the accepted request parameters when fetching a random quote.
+/// Defines the accepted request parameters when fetching random quotes.
 ///
 pub type GetRandomQuoteRequest = GetRandomQuoteRequestParams;
 
+///
+/// Defines the response returned when fetching random quotes.
+///
+pub type GetRandomQuoteResponse = Vec<GetQuotesResponseItem>;
+
+///
+/// The maximum number of quotes that can be fetched at random in a single request.
+///
+pub const MAX_RANDOM_QUOTE_COUNT: u32 = 50;
+
+///
+/// The maximum number of characters in a search query.
+///
+pub const MAX_SEARCH_LENGTH: usize = 200;
+
+///
+/// Defines the accepted request parameters when fetching the quote of the day.
+///
+pub type GetDailyQuoteRequest = GetDailyQuoteRequestParams;
+
+///
+/// Defines the response returned when fetching the quote of the day.
+///
+pub type GetDailyQuoteResponse = GetQuotesResponseItem;
+
+///
+/// Defines the request to fetch a single quote by its ID.
+///
+pub type GetQuoteRequest = GetQuoteRequestParams;
+
+///
+/// Defines the response returned when fetching a single quote by its ID.
+///
+pub type GetQuoteResponse = GetQuotesResponseItem;
+
+///
+/// Defines the body of a request to create a quote.
+///
+pub type CreateQuoteRequest = SaveQuoteRequestBody;
+
+///
+/// Defines the response returned when a quote has been created.
+///
+pub type CreateQuoteResponse = GetQuotesResponseItem;
+
+///
+/// Defines the body of a request to update a quote.
+/// The parts of the quote are replaced with the ones in the request.
+///
+pub type UpdateQuoteRequest = SaveQuoteRequestBody;
+
+///
+/// Defines the response returned when a quote has been updated.
+///
+pub type UpdateQuoteResponse = GetQuotesResponseItem;
+
+///
+/// Defines the response returned when a quote has been deleted.
+/// The response contains the quote as it was before being deleted.
+///
+pub type DeleteQuoteResponse = GetQuotesResponseItem;
+
 //HACK: Cant use PagedRequest<GetQuotesParams>
 // because of bug: https://github.com/nox/serde_urlencoded/issues/33
 // Workaround using DisplayFromStr doesnt work for Option fields.
@@ -24,9 +92,20 @@ pub type GetQuotesRequest = GetQuotesParams; // PagedRequest<GetQuotesParams>;
 pub type GetQuotesResponse = PagedResponse<GetQuotesResponseItem>;
 
 ///
-/// Defines the accepted request parameters when fetching a random quote.
+/// Defines the request parameters that are supported when searching quotes.
+///
+pub type SearchQuotesRequest = SearchQuotesParams;
+
+///
+/// Defines the response returned when searching quotes.
 ///
-#[derive(Debug, Deserialize, Serialize)]
+pub type SearchQuotesResponse = PagedResponse<SearchQuotesResponseItem>;
+
+///
+/// Defines the accepted request parameters when fetching random quotes.
+///
+#[derive(Debug, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct GetRandomQuoteRequestParams {
     ///
     /// The name of the show to fetch a quote for.
@@ -35,11 +114,13 @@ pub struct GetRandomQuoteRequestParams {
 
     ///
     /// The number of season within the show to fetch a quote for.
+    /// Can only be given along with `show_name`.
     ///
     pub season_no: Option<i32>,
 
     ///
     /// The number of episode within the season to fetch a quote for.
+    /// Can only be given along with `season_no`.
     ///
     pub episode_no: Option<i32>,
 
@@ -48,13 +129,107 @@ pub struct GetRandomQuoteRequestParams {
     /// Note that quotes may span multiple characters.
     ///
     pub character_name: Option<String>,
+
+    ///
+    /// The number of quotes to fetch.
+    /// Defaults to `1`, and can be at most `MAX_RANDOM_QUOTE_COUNT`.
+    /// Fewer quotes are returned when not enough match.
+    ///
+    pub count: Option<u32>,
+
+    ///
+    /// Seeds the random selection, so that the same request returns the same quotes
+    /// in the same order, for as long as the matching quotes don't change.
+    ///
+    pub seed: Option<u64>,
+}
+
+///
+/// Defines the accepted request parameters when fetching the quote of the day.
+///
+#[derive(Debug, Default, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GetDailyQuoteRequestParams {
+    ///
+    /// The name of the show to fetch the quote of the day for.
+    ///
+    pub show_name: Option<String>,
+
+    ///
+    /// The name of the character who is involved in the quote.
+    ///
+    pub character_name: Option<String>,
+
+    ///
+    /// The IANA name of the timezone whose calendar day is used, e.g. `Europe/London`.
+    /// Defaults to `UTC`.
+    ///
+    pub timezone: Option<String>,
+}
+
+///
+/// Defines the accepted request parameters when fetching a single quote.
+///
+/// Example request URL:
+///      http://base-url/api/quote/{id}
+///
+#[derive(Debug, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GetQuoteRequestParams {
+    ///
+    /// The ID of the quote to fetch.
+    ///
+    pub id: i32,
+}
+
+///
+/// Defines the body of a request to create or update a quote.
+///
+/// Example request body:
+/// ```json
+/// {
+///   "show_name": "Futurama",
+///   "season_no": 1,
+///   "episode_no": 1,
+///   "parts": [
+///     { "order": 1, "character_name": "Fry", "quote_text": "..." },
+///     { "order": 2, "character_name": "Leela", "quote_text": "..." }
+///   ]
+/// }
+/// ```
+///
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct SaveQuoteRequestBody {
+    ///
+    /// The name of the show the quote belongs to.
+    /// The show must already exist.
+    ///
+    pub show_name: String,
+
+    ///
+    /// The number of the season within the show that the quote belongs to.
+    /// The season must already exist.
+    ///
+    pub season_no: i32,
+
+    ///
+    /// The number of the episode within the season that the quote belongs to.
+    /// The episode must already exist.
+    ///
+    pub episode_no: i32,
+
+    ///
+    /// The parts that make up the entire quote.
+    /// Characters that don't yet exist in the show are created.
+    ///
+    pub parts: Vec<QuotePart>,
 }
 
 ///
 /// Defines part of a quote that will be included in the response.
 /// A quote part can be considered a single characters line within a conversation.
 ///
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
 pub struct QuotePart {
     ///
     /// The character being quoted.
@@ -75,8 +250,14 @@ pub struct QuotePart {
 ///
 /// Defines a quote that was selected at random.
 ///
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
 pub struct GetQuotesResponseItem {
+    ///
+    /// The unique ID of the quote.
+    /// This can be used to fetch the same quote again.
+    ///
+    pub id: i32,
+
     ///
     /// The name of the show the quote belongs to.
     ///
@@ -117,22 +298,35 @@ pub struct GetQuotesResponseItem {
 /// Example request URL:
 ///      http://base-url/api/show/{show}/season/{season}/episode/{episode}/quotes
 ///
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 #[serde(default)]
 pub struct GetQuotesParams {
     // Temporarily include page params on this struct due to bug in serde
     ///
     /// The page number to be fetched.
-    /// Defaults to `1`.
+    /// Defaults to `1`, and can be at most `10000`.
     ///
     pub page: u64,
 
     ///
     /// The maximum number of items to include on the page.
-    /// Defaults to `10``.
+    /// Defaults to `10``, and can be at most `100`.
     ///
     pub limit: u64,
 
+    ///
+    /// The `next_cursor` returned with the previous page.
+    /// When given, `page` must be left as `1`.
+    ///
+    pub cursor: Option<String>,
+
+    ///
+    /// Whether to include the `total` number of quotes and `total_pages` in the response.
+    /// Defaults to `false`.
+    ///
+    pub include_total: bool,
+
     ///
     /// The name of the show.
     ///
@@ -140,16 +334,138 @@ pub struct GetQuotesParams {
 
     ///
     /// The number of the season within the show.
+    /// Can only be given along with `show_name`.
     ///
     // #[serde_as(as = "DisplayFromStr")]
     pub season_no: Option<i32>,
 
     ///
     /// The number of the episode within the season.
+    /// Can only be given along with `season_no`.
+    ///
+    pub episode_no: Option<i32>,
+
+    ///
+    /// The order that the quotes are returned in.
+    /// Defaults to `chronological`.
+    ///
+    pub sort: QuoteSort,
+
+    ///
+    /// The direction of the `sort`.
+    /// Defaults to `asc`.
+    ///
+    pub direction: SortDirection,
+
+    ///
+    /// Seeds the order of the quotes when sorting by `random`, which is required
+    /// for that sort. Pages requested with the same seed don't overlap.
+    ///
+    pub seed: Option<u64>,
+}
+
+///
+/// The orders that quotes can be listed in.
+/// Quotes that are equal for the sort are ordered by their ID.
+///
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum QuoteSort {
+    ///
+    /// By show name, then the season and episode numbers, then the order the quotes were added.
+    ///
+    #[default]
+    Chronological,
+
+    ///
+    /// By the name of the character who says the first part of the quote.
+    ///
+    Character,
+
+    ///
+    /// By the total length of the text of the quote's parts.
+    ///
+    Length,
+
+    ///
+    /// In a random order that's decided by the `seed`.
+    ///
+    Random,
+}
+
+///
+/// Defines the request parameters that are supported when searching the text
+/// of quotes.
+///
+/// Example request URL:
+///      http://base-url/api/quotes/search?q=jealous&show_name=Futurama
+///
+#[derive(Debug, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(default)]
+pub struct SearchQuotesParams {
+    // Page params are included on this struct for the same reason as `GetQuotesParams`
+    ///
+    /// The page number to be fetched.
+    /// Defaults to `1`, and can be at most `10000`.
+    ///
+    pub page: u64,
+
+    ///
+    /// The maximum number of items to include on the page.
+    /// Defaults to `10``, and can be at most `100`.
+    ///
+    pub limit: u64,
+
+    ///
+    /// The text to search for.
+    /// Supports the web search syntax, e.g. quoted phrases, `or` and `-` to exclude words.
+    /// Can have at most `200` characters.
+    ///
+    pub q: String,
+
+    ///
+    /// The name of the show to search within.
+    ///
+    pub show_name: Option<String>,
+
+    ///
+    /// The number of the season within the show to search within.
+    /// Can only be given along with `show_name`.
+    ///
+    pub season_no: Option<i32>,
+
+    ///
+    /// The number of the episode within the season to search within.
+    /// Can only be given along with `season_no`.
     ///
     pub episode_no: Option<i32>,
 }
 
+///
+/// Defines a quote that matched a search.
+///
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+pub struct SearchQuotesResponseItem {
+    ///
+    /// The quote that matched the search.
+    ///
+    #[serde(flatten)]
+    pub quote: GetQuotesResponseItem,
+
+    ///
+    /// How closely the quote matched the search.
+    /// Results are ordered by this, with the best match first.
+    ///
+    pub rank: f32,
+
+    ///
+    /// An extract of the quote text as HTML, with the matching words wrapped in
+    /// `<mark></mark>` tags. HTML special characters in the text are escaped.
+    ///
+    pub snippet: String,
+}
+
 // ///
 // /// Defines the structure of a quote returned when fetching quotes for a given
 // /// episode.
@@ -169,6 +485,8 @@ impl Default for GetRandomQuoteRequestParams {
             season_no: Default::default(),
             episode_no: Default::default(),
             character_name: Default::default(),
+            count: Default::default(),
+            seed: Default::default(),
         }
     }
 }
@@ -182,6 +500,154 @@ impl Default for GetQuotesParams {
             episode_no: Default::default(),
             limit: pagination.limit,
             page: pagination.page,
+            cursor: pagination.cursor,
+            include_total: pagination.include_total,
+            sort: Default::default(),
+            direction: Default::default(),
+            seed: Default::default(),
         }
     }
 }
+
+impl Default for SearchQuotesParams {
+    fn default() -> Self {
+        let pagination = PagedRequest::<i32>::default();
+        Self {
+            q: Default::default(),
+            show_name: Default::default(),
+            season_no: Default::default(),
+            episode_no: Default::default(),
+            limit: pagination.limit,
+            page: pagination.page,
+        }
+    }
+}
+
+impl Validate for GetRandomQuoteRequestParams {
+    fn rules(&self, rules: &mut Rules) {
+        location_rules(rules, &self.show_name, self.season_no, self.episode_no);
+        rules.length(
+            "character_name",
+            self.character_name.as_deref(),
+            0,
+            MAX_NAME_LENGTH,
+        );
+        if let Some(count) = self.count {
+            rules.range("count", count, 1, MAX_RANDOM_QUOTE_COUNT);
+        }
+    }
+}
+
+impl Validate for GetDailyQuoteRequestParams {
+    fn rules(&self, rules: &mut Rules) {
+        rules
+            .length("show_name", self.show_name.as_deref(), 0, MAX_NAME_LENGTH)
+            .length(
+                "character_name",
+                self.character_name.as_deref(),
+                0,
+                MAX_NAME_LENGTH,
+            );
+    }
+}
+
+impl Validate for GetQuoteRequestParams {
+    fn rules(&self, rules: &mut Rules) {
+        rules.min("id", Some(self.id), 1);
+    }
+}
+
+impl Validate for SaveQuoteRequestBody {
+    fn rules(&self, rules: &mut Rules) {
+        rules
+            .length("show_name", self.show_name.as_str(), 1, MAX_NAME_LENGTH)
+            .min("season_no", Some(self.season_no), 1)
+            .min("episode_no", Some(self.episode_no), 1)
+            .check(
+                "parts",
+                !self.parts.is_empty(),
+                "A quote must have at least one part",
+            );
+
+        let mut orders = HashSet::new();
+        for (i, part) in self.parts.iter().enumerate() {
+            let field = format!("parts[{i}]");
+            rules.nested(&field, part).check(
+                &format!("{field}.order"),
+                orders.insert(part.order),
+                "Each part must have a unique order",
+            );
+        }
+    }
+}
+
+impl Validate for QuotePart {
+    fn rules(&self, rules: &mut Rules) {
+        rules
+            .length(
+                "character_name",
+                self.character_name.as_str(),
+                1,
+                MAX_NAME_LENGTH,
+            )
+            .check(
+                "quote_text",
+                !self.quote_text.trim().is_empty(),
+                "Is required",
+            );
+    }
+}
+
+impl Validate for GetQuotesParams {
+    fn rules(&self, rules: &mut Rules) {
+        page_rules(rules, self.page, self.limit, &self.cursor);
+        location_rules(rules, &self.show_name, self.season_no, self.episode_no);
+        rules
+            .check(
+                "seed",
+                self.sort != QuoteSort::Random || self.seed.is_some(),
+                "A seed is required to sort randomly",
+            )
+            .check(
+                "cursor",
+                self.sort == QuoteSort::Chronological || self.cursor.is_none(),
+                "Cursors can only be used with the chronological sort",
+            );
+    }
+}
+
+impl Validate for SearchQuotesParams {
+    fn rules(&self, rules: &mut Rules) {
+        page_rules(rules, self.page, self.limit, &None);
+        location_rules(rules, &self.show_name, self.season_no, self.episode_no);
+        rules.length("q", self.q.as_str(), 1, MAX_SEARCH_LENGTH);
+    }
+}
+
+///
+/// Declares the rules for filtering quotes by where they're from. A season can
+/// only be picked within a show, and an episode within a season.
+///
+fn location_rules(
+    rules: &mut Rules,
+    show_name: &Option<String>,
+    season_no: Option<i32>,
+    episode_no: Option<i32>,
+) {
+    rules
+        .length("show_name", show_name.as_deref(), 0, MAX_NAME_LENGTH)
+        .requires(
+            "season_no",
+            season_no.is_some(),
+            "show_name",
+            show_name.is_some(),
+        )
+        .requires(
+            "episode_no",
+            episode_no.is_some(),
+            "season_no",
+            season_no.is_some(),
+        )
+        .min("season_no", season_no, 1)
+        .min("episode_no", episode_no, 1);
+}
@@ -1,7 +1,11 @@
 use sea_orm::FromQueryResult;
 use serde::{Deserialize, Serialize};
 
-use crate::page::{PagedRequest, PagedResponse};
+use crate::{
+    page::{PagedRequest, PagedResponse},
+    validate::{Rules, Validate, MAX_NAME_LENGTH},
+};
+use utoipa::{IntoParams, ToSchema};
 
 ///
 /// Defines the request to fetch seasons for a given show
@@ -18,7 +22,8 @@ pub type GetSeasonsInShowResponse = PagedResponse<GetSeasonsInShowResponseItem>;
 /// In addition to the parameters defined here, parameters from
 /// `GetShowSeasonsRequestParams` are also supported.
 ///
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct GetSeasonsInShowRequestParams {
     ///
     /// The name of the show to fetch seasons for
@@ -30,7 +35,7 @@ pub struct GetSeasonsInShowRequestParams {
 /// The data that will be returned for a given season when fetching the seasons
 /// for a given show.
 ///
-#[derive(Serialize, Deserialize, FromQueryResult, Debug, Clone)]
+#[derive(Serialize, Deserialize, FromQueryResult, Debug, Clone, ToSchema)]
 pub struct GetSeasonsInShowResponseItem {
     ///
     /// The season number
@@ -54,3 +59,9 @@ impl Default for GetSeasonsInShowRequestParams {
         }
     }
 }
+
+impl Validate for GetSeasonsInShowRequestParams {
+    fn rules(&self, rules: &mut Rules) {
+        rules.length("show", self.show.as_str(), 1, MAX_NAME_LENGTH);
+    }
+}
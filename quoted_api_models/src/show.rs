@@ -1,7 +1,11 @@
 use sea_orm::FromQueryResult;
 use serde::{Deserialize, Serialize};
 
-use crate::page::{PagedRequest, PagedResponse};
+use crate::{
+    page::{PagedRequest, PagedResponse},
+    validate::{Rules, Validate, MAX_NAME_LENGTH},
+};
+use utoipa::{IntoParams, ToSchema};
 
 ///
 /// Defines a request to fetch a page of shows.
@@ -17,7 +21,8 @@ pub type GetShowsResponse = PagedResponse<GetShowsResponseItem>;
 /// Defines the accepted request parameters when fetching a page of shows.
 /// These are in addition to the parameters provided by `PagedRequest`.
 ///
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct GetShowsRequestParams {
     ///
     /// Optional query parameter to search shows based on their name
@@ -28,7 +33,7 @@ pub struct GetShowsRequestParams {
 ///
 /// Defines the structure of a show will be included in the response.
 ///
-#[derive(Serialize, Deserialize, FromQueryResult, Debug, Clone)]
+#[derive(Serialize, Deserialize, FromQueryResult, Debug, Clone, ToSchema)]
 pub struct GetShowsResponseItem {
     ///
     /// The name of the show
@@ -48,3 +53,9 @@ impl Default for GetShowsRequestParams {
         }
     }
 }
+
+impl Validate for GetShowsRequestParams {
+    fn rules(&self, rules: &mut Rules) {
+        rules.length("name", self.name.as_deref(), 0, MAX_NAME_LENGTH);
+    }
+}
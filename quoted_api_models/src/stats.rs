@@ -0,0 +1,182 @@
+use sea_orm::FromQueryResult;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::validate::{Rules, Validate, MAX_NAME_LENGTH};
+
+///
+/// The maximum number of characters and episodes that can be ranked.
+///
+pub const MAX_STATS_TOP: u32 = 50;
+
+///
+/// The number of characters and episodes ranked when `top` isn't given.
+///
+pub const DEFAULT_STATS_TOP: u32 = 10;
+
+///
+/// Defines the request to fetch statistics about the quotes in the catalogue.
+///
+pub type GetStatsRequest = GetStatsRequestParams;
+
+///
+/// Defines the response returned when fetching statistics about the quotes in the catalogue.
+///
+pub type GetStatsResponse = GetStatsResponseBody;
+
+///
+/// Defines the accepted request parameters when fetching statistics.
+///
+/// Example request URL:
+///      http://base-url/api/stats?show_name=Futurama&top=5
+///
+#[derive(Debug, Default, Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GetStatsRequestParams {
+    ///
+    /// The name of the show to limit the statistics to.
+    /// Every show is included when this isn't given.
+    ///
+    pub show_name: Option<String>,
+
+    ///
+    /// The number of most quoted characters and episodes to include.
+    /// Defaults to `DEFAULT_STATS_TOP`, and can be at most `MAX_STATS_TOP`.
+    ///
+    pub top: Option<u32>,
+}
+
+///
+/// Defines the statistics about the quotes in the catalogue, or in a single show.
+///
+/// Example response body:
+/// ```json
+/// {
+///   "totals": { "shows": 1, "seasons": 7, "episodes": 140, "characters": 210, "quotes": 1500, "quote_parts": 2400 },
+///   "average_parts_per_quote": 1.6,
+///   "top_characters": [{ "show_name": "Futurama", "name": "Bender", "quote_count": 320 }],
+///   "top_episodes": [{ "show_name": "Futurama", "season_no": 2, "episode_no": 1, "episode_name": "I Second That Emotion", "quote_count": 25 }]
+/// }
+/// ```
+///
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+pub struct GetStatsResponseBody {
+    ///
+    /// The number of each kind of item in the catalogue.
+    ///
+    pub totals: StatsTotals,
+
+    ///
+    /// The average number of parts in each quote, or `0` when there are no quotes.
+    ///
+    pub average_parts_per_quote: f64,
+
+    ///
+    /// The characters involved in the most quotes, most quoted first.
+    ///
+    pub top_characters: Vec<CharacterStats>,
+
+    ///
+    /// The episodes with the most quotes, most quoted first.
+    ///
+    pub top_episodes: Vec<EpisodeStats>,
+}
+
+///
+/// The number of each kind of item in the catalogue.
+///
+#[derive(Deserialize, Serialize, FromQueryResult, Debug, Clone, ToSchema)]
+pub struct StatsTotals {
+    ///
+    /// The number of shows.
+    ///
+    pub shows: i64,
+
+    ///
+    /// The number of seasons.
+    ///
+    pub seasons: i64,
+
+    ///
+    /// The number of episodes.
+    ///
+    pub episodes: i64,
+
+    ///
+    /// The number of characters, or those linked to the show when there is one.
+    ///
+    pub characters: i64,
+
+    ///
+    /// The number of quotes.
+    ///
+    pub quotes: i64,
+
+    ///
+    /// The number of parts across every quote.
+    ///
+    pub quote_parts: i64,
+}
+
+///
+/// A character along with the number of quotes they're involved in within a show.
+/// Characters in multiple shows are ranked separately within each of them.
+///
+#[derive(Deserialize, Serialize, FromQueryResult, Debug, Clone, ToSchema)]
+pub struct CharacterStats {
+    ///
+    /// The name of the show the character is in.
+    ///
+    pub show_name: String,
+
+    ///
+    /// The name of the character.
+    ///
+    pub name: String,
+
+    ///
+    /// The number of quotes in the show that the character is involved in. Quotes are
+    /// counted once, no matter how many of their parts the character is involved in.
+    ///
+    pub quote_count: i32,
+}
+
+///
+/// An episode along with the number of quotes in it.
+///
+#[derive(Deserialize, Serialize, FromQueryResult, Debug, Clone, ToSchema)]
+pub struct EpisodeStats {
+    ///
+    /// The name of the show the episode is in.
+    ///
+    pub show_name: String,
+
+    ///
+    /// The number of the season within the show.
+    ///
+    pub season_no: i32,
+
+    ///
+    /// The number of the episode within the season.
+    ///
+    pub episode_no: i32,
+
+    ///
+    /// The name of the episode, if it has one.
+    ///
+    pub episode_name: Option<String>,
+
+    ///
+    /// The number of quotes in the episode.
+    ///
+    pub quote_count: i32,
+}
+
+impl Validate for GetStatsRequestParams {
+    fn rules(&self, rules: &mut Rules) {
+        rules.length("show_name", self.show_name.as_deref(), 0, MAX_NAME_LENGTH);
+        if let Some(top) = self.top {
+            rules.range("top", top, 1, MAX_STATS_TOP);
+        }
+    }
+}
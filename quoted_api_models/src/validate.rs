@@ -0,0 +1,316 @@
+use std::fmt::Display;
+
+///
+/// The maximum number of characters in the name of a show or character.
+///
+pub const MAX_NAME_LENGTH: usize = 100;
+
+///
+/// A rule that a request didn't follow.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    ///
+    /// The name of the parameter or field, e.g. `season_no` or `parts[0].quote_text`.
+    ///
+    pub field: String,
+
+    ///
+    /// What's wrong with the value.
+    ///
+    pub message: String,
+}
+
+///
+/// Requests that declare the rules their values have to follow, so that they can
+/// be checked before the request is handled.
+///
+pub trait Validate {
+    ///
+    /// Declares the rules for the request, e.g.
+    /// `rules.range("limit", self.limit, 1, MAX_PAGE_LIMIT)`.
+    ///
+    fn rules(&self, rules: &mut Rules);
+
+    ///
+    /// Checks the request against its rules.
+    ///
+    /// # Errors
+    ///
+    /// Every rule that the request didn't follow.
+    ///
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut rules = Rules::default();
+        self.rules(&mut rules);
+
+        match rules.errors.is_empty() {
+            true => Ok(()),
+            false => Err(rules.errors),
+        }
+    }
+}
+
+///
+/// Collects the rules that a request didn't follow.
+///
+#[derive(Debug, Default)]
+pub struct Rules {
+    errors: Vec<ValidationError>,
+}
+
+impl Rules {
+    ///
+    /// The `field` must be true of the request.
+    ///
+    pub fn check(&mut self, field: &str, valid: bool, message: &str) -> &mut Self {
+        if !valid {
+            self.errors.push(ValidationError {
+                field: field.to_owned(),
+                message: message.to_owned(),
+            });
+        }
+        self
+    }
+
+    ///
+    /// The `field` must be between `min` and `max`, inclusive.
+    ///
+    pub fn range<T>(&mut self, field: &str, value: T, min: T, max: T) -> &mut Self
+    where
+        T: PartialOrd + Display,
+    {
+        let message = format!("Must be between {} and {}", min, max);
+        self.check(field, value >= min && value <= max, &message)
+    }
+
+    ///
+    /// The `field` must be at least `min`, when it's given.
+    ///
+    pub fn min<T>(&mut self, field: &str, value: Option<T>, min: T) -> &mut Self
+    where
+        T: PartialOrd + Display,
+    {
+        let message = format!("Must be at least {}", min);
+        self.check(field, value.is_none_or(|value| value >= min), &message)
+    }
+
+    ///
+    /// The `field` must have between `min` and `max` characters, ignoring any
+    /// leading or trailing whitespace, when it's given.
+    /// A `min` of `1` makes it required.
+    ///
+    pub fn length<'a>(
+        &mut self,
+        field: &str,
+        value: impl Into<Option<&'a str>>,
+        min: usize,
+        max: usize,
+    ) -> &mut Self {
+        let length = match value.into() {
+            None => return self,
+            Some(value) => value.trim().chars().count(),
+        };
+
+        let message = match length {
+            0 => "Is required".to_owned(),
+            _ => format!("Must have between {} and {} characters", min, max),
+        };
+        self.check(field, length >= min && length <= max, &message)
+    }
+
+    ///
+    /// The `field` can only be given along with the `required` field.
+    ///
+    pub fn requires(
+        &mut self,
+        field: &str,
+        given: bool,
+        required: &str,
+        required_given: bool,
+    ) -> &mut Self {
+        let message = format!("Can only be given along with {}", required);
+        self.check(field, !given || required_given, &message)
+    }
+
+    ///
+    /// The `value` must follow its own rules, which are reported under `field`,
+    /// e.g. `parts[0].quote_text`.
+    ///
+    pub fn nested<T>(&mut self, field: &str, value: &T) -> &mut Self
+    where
+        T: Validate,
+    {
+        if let Err(errors) = value.validate() {
+            self.errors
+                .extend(errors.into_iter().map(|error| ValidationError {
+                    field: format!("{}.{}", field, error.field),
+                    message: error.message,
+                }));
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Request<F>(F)
+    where
+        F: Fn(&mut Rules);
+
+    impl<F> Validate for Request<F>
+    where
+        F: Fn(&mut Rules),
+    {
+        fn rules(&self, rules: &mut Rules) {
+            (self.0)(rules)
+        }
+    }
+
+    fn errors<F>(rules: F) -> Vec<(String, String)>
+    where
+        F: Fn(&mut Rules),
+    {
+        match Request(rules).validate() {
+            Ok(()) => vec![],
+            Err(errors) => errors
+                .into_iter()
+                .map(|error| (error.field, error.message))
+                .collect(),
+        }
+    }
+
+    fn error(field: &str, message: &str) -> Vec<(String, String)> {
+        vec![(field.to_owned(), message.to_owned())]
+    }
+
+    #[test]
+    fn check_fails_when_false() {
+        assert!(errors(|rules| {
+            rules.check("sort", true, "Unsupported");
+        })
+        .is_empty());
+        assert_eq!(
+            errors(|rules| {
+                rules.check("sort", false, "Unsupported");
+            }),
+            error("sort", "Unsupported")
+        );
+    }
+
+    #[test]
+    fn range_includes_its_bounds() {
+        for value in [1, 50, 100] {
+            assert!(errors(|rules| {
+                rules.range("limit", value, 1, 100);
+            })
+            .is_empty());
+        }
+        for value in [0, 101] {
+            assert_eq!(
+                errors(|rules| {
+                    rules.range("limit", value, 1, 100);
+                }),
+                error("limit", "Must be between 1 and 100")
+            );
+        }
+    }
+
+    #[test]
+    fn min_includes_its_bound_and_ignores_missing_values() {
+        for value in [None, Some(1), Some(2)] {
+            assert!(errors(|rules| {
+                rules.min("season_no", value, 1);
+            })
+            .is_empty());
+        }
+        assert_eq!(
+            errors(|rules| {
+                rules.min("season_no", Some(0), 1);
+            }),
+            error("season_no", "Must be at least 1")
+        );
+    }
+
+    #[test]
+    fn length_includes_its_bounds_and_counts_trimmed_characters() {
+        for value in [None, Some("a"), Some(" abc "), Some("ééé")] {
+            assert!(errors(|rules| {
+                rules.length("name", value, 1, 3);
+            })
+            .is_empty());
+        }
+        assert_eq!(
+            errors(|rules| {
+                rules.length("name", "abcd", 1, 3);
+            }),
+            error("name", "Must have between 1 and 3 characters")
+        );
+        assert_eq!(
+            errors(|rules| {
+                rules.length("name", "a", 2, 3);
+            }),
+            error("name", "Must have between 2 and 3 characters")
+        );
+    }
+
+    #[test]
+    fn length_of_one_makes_blank_values_required() {
+        assert_eq!(
+            errors(|rules| {
+                rules.length("name", "   ", 1, 3);
+            }),
+            error("name", "Is required")
+        );
+        assert!(errors(|rules| {
+            rules.length("name", "   ", 0, 3);
+        })
+        .is_empty());
+    }
+
+    #[test]
+    fn requires_only_fails_without_the_required_field() {
+        for (given, required_given) in [(false, false), (false, true), (true, true)] {
+            assert!(errors(|rules| {
+                rules.requires("season_no", given, "show_name", required_given);
+            })
+            .is_empty());
+        }
+        assert_eq!(
+            errors(|rules| {
+                rules.requires("season_no", true, "show_name", false);
+            }),
+            error("season_no", "Can only be given along with show_name")
+        );
+    }
+
+    #[test]
+    fn nested_errors_are_reported_under_the_field() {
+        let part = Request(|rules: &mut Rules| {
+            rules.length("quote_text", "", 1, 10);
+        });
+
+        assert_eq!(
+            errors(|rules| {
+                rules.nested("parts[0]", &part);
+            }),
+            error("parts[0].quote_text", "Is required")
+        );
+    }
+
+    #[test]
+    fn every_failed_rule_is_reported() {
+        let errors = errors(|rules| {
+            rules.range("limit", 0, 1, 100).min("page", Some(0), 1);
+        });
+
+        assert_eq!(
+            errors
+                .iter()
+                .map(|(field, _)| field.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["limit", "page"]
+        );
+    }
+}
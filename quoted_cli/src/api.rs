@@ -21,6 +21,7 @@ pub async fn get_random(
         season_no: season,
         episode_no: episode,
         character_name: character,
+        ..Default::default()
     };
 
     let query_string = serde_urlencoded::to_string(request)
@@ -39,9 +40,11 @@ pub async fn get_random(
         .or_else(|e| Err(format!("Error calling API\n{e}")))?;
 
     let quote = response
-        .json::<api_models::quote::GetQuotesResponseItem>()
+        .json::<api_models::quote::GetRandomQuoteResponse>()
         .await
-        .or_else(|e| Err(format!("Error parsing response\n{e}")))?;
+        .or_else(|e| Err(format!("Error parsing response\n{e}")))?
+        .pop()
+        .ok_or("No quote found")?;
 
     log::trace!(
         "Found random quote {}",
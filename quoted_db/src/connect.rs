@@ -1,9 +1,15 @@
-use std::{env, str::FromStr};
+use std::{env, str::FromStr, time::Duration};
 
-use sea_orm::{ConnectOptions, DatabaseConnection};
+use sea_orm::{sqlx::postgres::PgSslMode, ConnectOptions, DatabaseConnection};
+use tokio::sync::OnceCell;
 
 use crate::error::DBError;
 
+///
+/// The pool shared by every caller of `get_default_connection` in the process.
+///
+static POOL: OnceCell<DatabaseConnection> = OnceCell::const_new();
+
 enum ConnectionEnvVar {
     Host,
     Port,
@@ -11,6 +17,12 @@ enum ConnectionEnvVar {
     Password,
     Url,
     Name,
+    MaxConnections,
+    MinConnections,
+    ConnectTimeout,
+    IdleTimeout,
+    StatementTimeout,
+    SslMode,
 }
 
 impl ToString for ConnectionEnvVar {
@@ -22,6 +34,12 @@ impl ToString for ConnectionEnvVar {
             ConnectionEnvVar::Password => "DATABASE_PASSWORD".to_owned(),
             ConnectionEnvVar::Url => "DATABASE_URL".to_owned(),
             ConnectionEnvVar::Name => "DATABASE_NAME".to_owned(),
+            ConnectionEnvVar::MaxConnections => "DATABASE_MAX_CONNECTIONS".to_owned(),
+            ConnectionEnvVar::MinConnections => "DATABASE_MIN_CONNECTIONS".to_owned(),
+            ConnectionEnvVar::ConnectTimeout => "DATABASE_CONNECT_TIMEOUT_SECS".to_owned(),
+            ConnectionEnvVar::IdleTimeout => "DATABASE_IDLE_TIMEOUT_SECS".to_owned(),
+            ConnectionEnvVar::StatementTimeout => "DATABASE_STATEMENT_TIMEOUT_MS".to_owned(),
+            ConnectionEnvVar::SslMode => "DATABASE_SSL_MODE".to_owned(),
         }
     }
 }
@@ -35,7 +53,23 @@ struct ConnectionParams {
     database: String,
 }
 
-/// Returns a new `DatabaseConnection` using values from env vars.
+/// Options for the pool, and for each connection in it.
+/// Options that aren't set are left to the defaults of sea-orm and sqlx.
+struct PoolOptions {
+    max_connections: Option<u32>,
+    min_connections: Option<u32>,
+    connect_timeout: Option<Duration>,
+    idle_timeout: Option<Duration>,
+    statement_timeout: Option<u64>,
+    ssl_mode: Option<PgSslMode>,
+}
+
+/// Returns the `DatabaseConnection` for the process, using values from env vars.
+///
+/// The pool is created by the first call, and every later call returns the same
+/// pool, so that warm serverless invocations and the requests handled by the
+/// local server reuse its connections. If creating the pool fails, the next call
+/// tries again.
 ///
 /// If `DATABASE_URL` is present, it will be used.
 ///
@@ -48,19 +82,61 @@ struct ConnectionParams {
 /// - `DATABASE_PASSWORD`
 /// - `DATABASE_NAME`
 ///
+/// The pool can be tuned with the following optional env vars:
+///
+/// - `DATABASE_MAX_CONNECTIONS`
+/// - `DATABASE_MIN_CONNECTIONS`
+/// - `DATABASE_CONNECT_TIMEOUT_SECS`
+/// - `DATABASE_IDLE_TIMEOUT_SECS`
+/// - `DATABASE_STATEMENT_TIMEOUT_MS`
+/// - `DATABASE_SSL_MODE`, e.g. `require` or `verify-full`
+///
 /// # Errors
 ///
 /// When any required env vars are missing or invalid.
 ///
 /// When a connection the database cannot be established.
 pub async fn get_default_connection() -> Result<DatabaseConnection, DBError> {
+    let db = POOL.get_or_try_init(connect).await?;
+
+    // Connections are handles to the same pool, so they're cheap to clone
+    return Ok(db.clone());
+}
+
+/// Creates the pool from the env vars.
+async fn connect() -> Result<DatabaseConnection, DBError> {
     let connection_string = get_connection_string()?;
+    let pool_options = get_pool_options()?;
 
-    // May want to configure these further.
     // See https://www.sea-ql.org/SeaORM/docs/install-and-config/connection/#connect-options
     let mut connection_opts = ConnectOptions::new(connection_string);
     connection_opts.sqlx_logging(false);
 
+    if let Some(max_connections) = pool_options.max_connections {
+        connection_opts.max_connections(max_connections);
+    }
+    if let Some(min_connections) = pool_options.min_connections {
+        connection_opts.min_connections(min_connections);
+    }
+    if let Some(connect_timeout) = pool_options.connect_timeout {
+        connection_opts.connect_timeout(connect_timeout);
+    }
+    if let Some(idle_timeout) = pool_options.idle_timeout {
+        connection_opts.idle_timeout(idle_timeout);
+    }
+
+    let statement_timeout = pool_options.statement_timeout;
+    let ssl_mode = pool_options.ssl_mode;
+    connection_opts.map_sqlx_postgres_opts(move |mut opts| {
+        if let Some(statement_timeout) = statement_timeout {
+            opts = opts.options([("statement_timeout", statement_timeout)]);
+        }
+        if let Some(ssl_mode) = ssl_mode {
+            opts = opts.ssl_mode(ssl_mode);
+        }
+        opts
+    });
+
     return Ok(sea_orm::Database::connect(connection_opts).await?);
 }
 
@@ -107,6 +183,46 @@ fn get_connection_params() -> Result<ConnectionParams, DBError> {
     });
 }
 
+/// Pulls out the optional env vars that tune the pool.
+///
+/// # Errors
+///
+/// If any of these env vars are invalid, or the pool can't be created with them,
+/// e.g. when the minimum number of connections is more than the maximum.
+fn get_pool_options() -> Result<PoolOptions, DBError> {
+    let options = PoolOptions {
+        max_connections: get_optional_env_var::<u32>(&ConnectionEnvVar::MaxConnections)?,
+        min_connections: get_optional_env_var::<u32>(&ConnectionEnvVar::MinConnections)?,
+        connect_timeout: get_optional_env_var::<u64>(&ConnectionEnvVar::ConnectTimeout)?
+            .map(Duration::from_secs),
+        idle_timeout: get_optional_env_var::<u64>(&ConnectionEnvVar::IdleTimeout)?
+            .map(Duration::from_secs),
+        statement_timeout: get_optional_env_var::<u64>(&ConnectionEnvVar::StatementTimeout)?,
+        ssl_mode: get_optional_env_var::<PgSslMode>(&ConnectionEnvVar::SslMode)?,
+    };
+
+    if options.max_connections == Some(0) {
+        return Err(DBError::ConnectionParamInvalid(
+            ConnectionEnvVar::MaxConnections.to_string(),
+        ));
+    }
+    if options.connect_timeout == Some(Duration::ZERO) {
+        return Err(DBError::ConnectionParamInvalid(
+            ConnectionEnvVar::ConnectTimeout.to_string(),
+        ));
+    }
+    if let (Some(min), Some(max)) = (options.min_connections, options.max_connections) {
+        if min > max {
+            return Err(DBError::ConnectionParamsConflict(
+                ConnectionEnvVar::MinConnections.to_string(),
+                ConnectionEnvVar::MaxConnections.to_string(),
+            ));
+        }
+    }
+
+    return Ok(options);
+}
+
 /// Gets the value from the specified `env_var` and converts it to `T`.
 ///
 /// # Errors
@@ -9,6 +9,8 @@ pub enum DBError {
     ConnectionParamRequired(String),
     #[error("Invalid value for connection parameter {0}")]
     ConnectionParamInvalid(String),
+    #[error("Connection parameter {0} can't be greater than {1}")]
+    ConnectionParamsConflict(String, String),
     #[error("Missing dependency for insert operation")]
     MissingInsertDependency,
 }
@@ -1,16 +1,22 @@
 mod connect;
 pub mod error;
 
+use std::time::{Duration, Instant};
+
 use error::DBError;
 use sea_orm::DatabaseConnection;
 
+/// Returns the `DatabaseConnection` shared by the process, creating the pool
+/// the first time it's called. The pool is configured from env vars.
 pub async fn get_default_connection() -> Result<DatabaseConnection, DBError> {
     connect::get_default_connection().await
 }
 
-pub fn enable_query_logging() {
-    let _ = tracing_subscriber::fmt()
-        .with_max_level(tracing_subscriber::filter::LevelFilter::DEBUG)
-        .with_test_writer()
-        .try_init();
+/// Checks that the database can be reached over one of the connections in the pool,
+/// returning how long the round trip took.
+pub async fn ping(db: &DatabaseConnection) -> Result<Duration, DBError> {
+    let started = Instant::now();
+    db.ping().await?;
+
+    Ok(started.elapsed())
 }
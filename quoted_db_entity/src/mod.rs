@@ -7,5 +7,8 @@ pub mod character_show;
 pub mod episode;
 pub mod quote;
 pub mod quote_part;
+pub mod rate_limit;
 pub mod season;
 pub mod show;
+pub mod user_token;
+pub mod users;
@@ -5,5 +5,8 @@ pub use super::character_show::Entity as CharacterShow;
 pub use super::episode::Entity as Episode;
 pub use super::quote::Entity as Quote;
 pub use super::quote_part::Entity as QuotePart;
+pub use super::rate_limit::Entity as RateLimit;
 pub use super::season::Entity as Season;
 pub use super::show::Entity as Show;
+pub use super::user_token::Entity as UserToken;
+pub use super::users::Entity as Users;
@@ -12,9 +12,19 @@ pub struct Model {
     pub email: String,
     #[sea_orm(column_type = "Text")]
     pub password: String,
+    pub role: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+    #[sea_orm(has_many = "super::user_token::Entity")]
+    UserToken,
+}
+
+impl Related<super::user_token::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserToken.def()
+    }
+}
 
 impl ActiveModelBehavior for ActiveModel {}
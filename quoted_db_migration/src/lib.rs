@@ -8,6 +8,9 @@ mod m20240914_200407_create_quote_table;
 mod m20240914_214423_add_unique_constraint_to_quote;
 mod m20241001_185027_add_season_name;
 mod m20241006_082413_multi_part_quote;
+mod m20241019_101500_quote_part_search_vector;
+mod m20241026_090000_user_auth;
+mod m20241102_090000_rate_limit;
 
 pub struct Migrator;
 
@@ -23,6 +26,9 @@ impl MigratorTrait for Migrator {
             Box::new(m20240914_214423_add_unique_constraint_to_quote::Migration),
             Box::new(m20241001_185027_add_season_name::Migration),
             Box::new(m20241006_082413_multi_part_quote::Migration),
+            Box::new(m20241019_101500_quote_part_search_vector::Migration),
+            Box::new(m20241026_090000_user_auth::Migration),
+            Box::new(m20241102_090000_rate_limit::Migration),
         ]
     }
 }
@@ -0,0 +1,71 @@
+use sea_orm::Statement;
+use sea_orm_migration::prelude::*;
+
+/*
+   ==========================================
+   Adds a generated tsvector column to QuotePart so that the text of
+   quotes can be searched. Postgres keeps the column up to date whenever
+   QuotePart.Value changes, and a GIN index is used to make the search fast.
+   ==========================================
+*/
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const INDEX_NAME: &str = "quote_part_search_vector_idx";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SeaQuery doesn't support generated columns or GIN indexes, so raw SQL is used here.
+        let db = manager.get_connection();
+
+        db.execute(Statement::from_string(
+            db.get_database_backend(),
+            "
+            alter table quote_part
+            add column if not exists search_vector tsvector
+            generated always as (to_tsvector('english', value)) stored;
+            ",
+        ))
+        .await?;
+
+        db.execute(Statement::from_string(
+            db.get_database_backend(),
+            format!(
+                "create index if not exists {} on quote_part using gin (search_vector);",
+                INDEX_NAME
+            ),
+        ))
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                IndexDropStatement::new()
+                    .if_exists()
+                    .table(QuotePart::Table)
+                    .name(INDEX_NAME)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(QuotePart::Table)
+                    .drop_column(QuotePart::SearchVector)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum QuotePart {
+    Table,
+    SearchVector,
+}
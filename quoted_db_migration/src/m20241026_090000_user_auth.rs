@@ -0,0 +1,113 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+/*
+   ==========================================
+   Adds what's needed to authenticate users of the API.
+   The Users table may already exist (it predates the migrations), so it's
+   only created when missing. Each user is given a Role, and the tokens
+   issued when a user logs in are stored, hashed, in UserToken.
+   ==========================================
+*/
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. create Users table, if it doesn't already exist
+        manager
+            .create_table(
+                Table::create()
+                    .table(Users::Table)
+                    .if_not_exists()
+                    .col(uuid(Users::Id).primary_key())
+                    .col(string(Users::Name))
+                    .col(text(Users::Email).unique_key())
+                    .col(text(Users::Password))
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. add Users.Role, making any existing users contributors
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new_with_type(
+                            Users::Role,
+                            ColumnType::String(StringLen::default()),
+                        )
+                        .not_null()
+                        .default("contributor")
+                        .check(Expr::col(Users::Role).is_in(["admin", "contributor"]))
+                        .to_owned(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 3. create UserToken table
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserToken::Table)
+                    .if_not_exists()
+                    .col(pk_auto(UserToken::Id))
+                    .col(uuid(UserToken::UserId))
+                    .col(text(UserToken::TokenHash).unique_key())
+                    .col(
+                        timestamp_with_time_zone(UserToken::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(timestamp_with_time_zone(UserToken::ExpiresAt))
+                    .foreign_key(
+                        ForeignKeyCreateStatement::new()
+                            .from_tbl(UserToken::Table)
+                            .from_col(UserToken::UserId)
+                            .to_tbl(Users::Table)
+                            .to_col(Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The Users table is left in place, since it may have existed before this migration.
+        manager
+            .drop_table(Table::drop().table(UserToken::Table).if_exists().to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::Role)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+    Name,
+    Email,
+    Password,
+    Role,
+}
+
+#[derive(DeriveIden)]
+enum UserToken {
+    Table,
+    Id,
+    UserId,
+    TokenHash,
+    CreatedAt,
+    ExpiresAt,
+}
@@ -0,0 +1,58 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+/*
+   ==========================================
+   Adds the RateLimit table, which holds a token bucket for each client of
+   each endpoint. The buckets are kept in the database rather than in memory
+   so that every serverless instance shares them.
+   ==========================================
+*/
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 1. create RateLimit table
+        manager
+            .create_table(
+                Table::create()
+                    .table(RateLimit::Table)
+                    .if_not_exists()
+                    .col(text(RateLimit::Key).primary_key())
+                    .col(double(RateLimit::Tokens))
+                    .col(
+                        timestamp_with_time_zone(RateLimit::UpdatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 2. index RateLimit.UpdatedAt, so that stale buckets can be removed
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-rate_limit-updated_at")
+                    .table(RateLimit::Table)
+                    .col(RateLimit::UpdatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RateLimit::Table).if_exists().to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RateLimit {
+    Table,
+    Key,
+    Tokens,
+    UpdatedAt,
+}
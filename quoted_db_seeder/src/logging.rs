@@ -0,0 +1,24 @@
+use tracing_subscriber::EnvFilter;
+
+///
+/// The env var with the log levels, using the `tracing_subscriber` directive syntax
+/// in the same way as the API, e.g. `info` or `info,sea_orm=debug`.
+///
+const LOG_LEVEL_ENV_VAR: &str = "LOG_LEVEL";
+
+///
+/// Queries are logged by `sea_orm` at `debug`, so they're included by default
+/// to show the progress of the seed.
+///
+const DEFAULT_LOG_LEVEL: &str = "info,sea_orm=debug";
+
+///
+/// Installs the global `tracing` subscriber, logging to stdout at the levels in
+/// `LOG_LEVEL`, which defaults to `DEFAULT_LOG_LEVEL`.
+///
+pub fn init() {
+    let filter = EnvFilter::try_from_env(LOG_LEVEL_ENV_VAR)
+        .unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_LEVEL));
+
+    let _ = tracing_subscriber::fmt().with_env_filter(filter).try_init();
+}
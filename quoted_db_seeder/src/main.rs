@@ -4,6 +4,7 @@ mod client;
 mod db_helper;
 mod error;
 mod id;
+mod logging;
 mod seeder;
 mod sheets;
 
@@ -12,18 +13,18 @@ use dotenvy::dotenv;
 use error::SeedError;
 use google_sheets4 as sheets4;
 use id::IdFactory;
-use quoted_db::{enable_query_logging, get_default_connection};
+use quoted_db::get_default_connection;
 use quoted_db_migration::{Migrator, MigratorTrait};
 use sheets4::Sheets;
 
 #[tokio::main]
 async fn main() -> Result<(), SeedError> {
     let _ = dotenv();
+    logging::init();
 
     let args = cli::Args::parse();
 
     let db = get_default_connection().await?;
-    enable_query_logging();
 
     let mut id_factory = IdFactory::new(&db);
 
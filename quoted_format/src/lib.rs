@@ -0,0 +1,424 @@
+use std::fmt::Display;
+
+use quoted_api_models::{
+    page::PagedResponse,
+    quote::{GetQuotesResponseItem, SearchQuotesResponseItem},
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+///
+/// The formats that quotes can be rendered in.
+///
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    ///
+    /// The quotes as JSON.
+    ///
+    #[default]
+    Json,
+
+    ///
+    /// Each quote laid out as it's printed by the CLI, separated by `%` lines
+    /// like a fortune file.
+    ///
+    Text,
+
+    ///
+    /// One row for each part of each quote, with a header row.
+    ///
+    Csv,
+
+    ///
+    /// Newline delimited JSON, with one quote on each line.
+    ///
+    Ndjson,
+}
+
+impl Format {
+    ///
+    /// The value of the `Content-Type` header for the format.
+    ///
+    pub fn content_type(&self) -> &'static str {
+        return match self {
+            Format::Json => "application/json",
+            Format::Text => "text/plain; charset=utf-8",
+            Format::Csv => "text/csv; charset=utf-8",
+            Format::Ndjson => "application/x-ndjson",
+        };
+    }
+
+    ///
+    /// Gets the format for a media type, e.g. `text/csv`.
+    /// Wildcards are matched to JSON, or plain text for `text/*`.
+    ///
+    pub fn from_media_type(media_type: &str) -> Option<Format> {
+        return match media_type.trim().to_ascii_lowercase().as_str() {
+            "application/json" | "application/*" | "*/*" => Some(Format::Json),
+            "text/plain" | "text/*" => Some(Format::Text),
+            "text/csv" => Some(Format::Csv),
+            "application/x-ndjson" | "application/ndjson" => Some(Format::Ndjson),
+            _ => None,
+        };
+    }
+
+    ///
+    /// Picks the format the client prefers from an `Accept` header, taking the
+    /// quality (`q`) of each media type into account.
+    /// Returns `None` when none of the media types are supported.
+    ///
+    pub fn from_accept(accept: &str) -> Option<Format> {
+        let mut best: Option<(Format, f32)> = None;
+
+        for media_range in accept.split(',') {
+            let mut params = media_range.split(';');
+            let format = match params.next().and_then(Format::from_media_type) {
+                None => continue,
+                Some(format) => format,
+            };
+            let quality = params
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+
+            // Earlier media types win when the quality is the same
+            if quality > 0.0 && best.is_none_or(|(_, best_quality)| quality > best_quality) {
+                best = Some((format, quality));
+            }
+        }
+
+        best.map(|(format, _)| format)
+    }
+}
+
+///
+/// An error rendering quotes.
+///
+#[derive(Debug)]
+pub enum Error {
+    Csv(csv::Error),
+    Json(serde_json::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return match self {
+            Error::Csv(e) => write!(f, "Error writing CSV\n{e}"),
+            Error::Json(e) => write!(f, "Error writing JSON\n{e}"),
+        };
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<csv::Error> for Error {
+    fn from(e: csv::Error) -> Self {
+        Error::Csv(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Json(e)
+    }
+}
+
+///
+/// Responses made up of quotes, which can be rendered with `render`.
+///
+pub trait Quotes {
+    ///
+    /// The quotes in the response, in the order they should be rendered.
+    ///
+    fn quotes(&self) -> Vec<&GetQuotesResponseItem>;
+}
+
+impl Quotes for GetQuotesResponseItem {
+    fn quotes(&self) -> Vec<&GetQuotesResponseItem> {
+        vec![self]
+    }
+}
+
+impl Quotes for Vec<GetQuotesResponseItem> {
+    fn quotes(&self) -> Vec<&GetQuotesResponseItem> {
+        self.iter().collect()
+    }
+}
+
+impl Quotes for PagedResponse<GetQuotesResponseItem> {
+    fn quotes(&self) -> Vec<&GetQuotesResponseItem> {
+        self.data.iter().collect()
+    }
+}
+
+impl Quotes for PagedResponse<SearchQuotesResponseItem> {
+    fn quotes(&self) -> Vec<&GetQuotesResponseItem> {
+        self.data.iter().map(|item| &item.quote).collect()
+    }
+}
+
+///
+/// Renders the response in the given format.
+///
+/// JSON is the whole response, while the other formats only render the quotes
+/// themselves, so any paging details of the response are left out.
+///
+pub fn render<Q>(response: &Q, format: Format) -> Result<String, Error>
+where
+    Q: Quotes + Serialize + ?Sized,
+{
+    let quotes = response.quotes();
+
+    return match format {
+        Format::Json => Ok(serde_json::to_string(response)?),
+        Format::Text => Ok(quotes
+            .iter()
+            .map(|quote| TextQuote(quote).to_string() + "\n")
+            .collect::<Vec<String>>()
+            .join("%\n")),
+        Format::Csv => render_csv(&quotes),
+        Format::Ndjson => {
+            let mut ndjson = String::new();
+            for quote in quotes {
+                ndjson += &serde_json::to_string(quote)?;
+                ndjson += "\n";
+            }
+            Ok(ndjson)
+        }
+    };
+}
+
+///
+/// A row of the CSV, for a single part of a quote.
+///
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    id: i32,
+    show_name: &'a str,
+    season_no: i32,
+    season_name: Option<&'a str>,
+    episode_no: i32,
+    episode_name: Option<&'a str>,
+    order: i32,
+    character_name: &'a str,
+    quote_text: &'a str,
+}
+
+fn render_csv(quotes: &[&GetQuotesResponseItem]) -> Result<String, Error> {
+    // The header is written explicitly so that it's there even without any quotes
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(vec![]);
+    writer.write_record([
+        "id",
+        "show_name",
+        "season_no",
+        "season_name",
+        "episode_no",
+        "episode_name",
+        "order",
+        "character_name",
+        "quote_text",
+    ])?;
+
+    for quote in quotes {
+        for part in sorted_parts(quote) {
+            writer.serialize(CsvRow {
+                id: quote.id,
+                show_name: &quote.show_name,
+                season_no: quote.season_no,
+                season_name: quote.season_name.as_deref(),
+                episode_no: quote.episode_no,
+                episode_name: quote.episode_name.as_deref(),
+                order: part.order,
+                character_name: &part.character_name,
+                quote_text: &part.quote_text,
+            })?;
+        }
+    }
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| csv::Error::from(e.into_error()))?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+///
+/// Displays a quote as plain text, with the quote first, followed by where it's from.
+///
+pub struct TextQuote<'a>(pub &'a GetQuotesResponseItem);
+
+impl Display for TextQuote<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let quote = self.0;
+        let parts = sorted_parts(quote);
+        let mut season = format!("{}", quote.season_no);
+        if let Some(season_name) = &quote.season_name {
+            season += format!(" - {}", season_name).as_str();
+        }
+        let mut episode = format!("{}", quote.episode_no);
+        if let Some(episode_name) = &quote.episode_name {
+            episode += format!(" - {}", episode_name).as_str();
+        }
+        if parts.len() == 1 {
+            // e.g
+            // It's OK to lie to women. They're not people like us
+            //
+            // Peter Griffin
+            // Family Guy
+            // Season 1
+            // Episode 1 - Death Has a Shadow
+            return write!(
+                f,
+                "{}\n\n{}\n{}\nSeason {}\nEpisode {}",
+                parts[0].quote_text, parts[0].character_name, quote.show_name, season, episode
+            );
+        }
+
+        let quote_parts = parts
+            .iter()
+            .map(|p| p.character_name.clone() + ": " + p.quote_text.as_str())
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        // e.g.
+        // Philip J. Fry: Does anybody else feel aroused and jealous and worried?
+        // Bender Rodriguez: I have't felt much of anything since my guinea pig died.
+        //
+        // Futurama
+        // Season 6
+        // Episode 2 - In-A-Gadda-Da-Leela
+        write!(
+            f,
+            "{}\n\n{}\nSeason {}\nEpisode {}",
+            quote_parts, quote.show_name, season, episode,
+        )
+    }
+}
+
+fn sorted_parts(quote: &GetQuotesResponseItem) -> Vec<&quoted_api_models::quote::QuotePart> {
+    let mut parts = quote.parts.iter().collect::<Vec<_>>();
+    parts.sort_by_key(|p| p.order);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quoted_api_models::quote::QuotePart;
+
+    fn quote() -> GetQuotesResponseItem {
+        GetQuotesResponseItem {
+            id: 7,
+            show_name: "Futurama".to_owned(),
+            season_no: 6,
+            season_name: None,
+            episode_no: 2,
+            episode_name: Some("In-A-Gadda-Da-Leela".to_owned()),
+            parts: vec![
+                QuotePart {
+                    character_name: "Bender".to_owned(),
+                    order: 2,
+                    quote_text: "Shut up, baby.".to_owned(),
+                },
+                QuotePart {
+                    character_name: "Fry".to_owned(),
+                    order: 1,
+                    quote_text: "Hi, Bender".to_owned(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn accept_picks_the_highest_quality() {
+        assert_eq!(
+            Format::from_accept("application/json;q=0.5, text/csv"),
+            Some(Format::Csv)
+        );
+        assert_eq!(
+            Format::from_accept("text/csv;q=0.2, application/x-ndjson;q=0.9"),
+            Some(Format::Ndjson)
+        );
+    }
+
+    #[test]
+    fn accept_prefers_earlier_media_types_with_the_same_quality() {
+        assert_eq!(
+            Format::from_accept("text/plain, text/csv"),
+            Some(Format::Text)
+        );
+        assert_eq!(
+            Format::from_accept("text/csv;q=0.5, text/plain;q=0.5"),
+            Some(Format::Csv)
+        );
+    }
+
+    #[test]
+    fn accept_skips_refused_and_unsupported_media_types() {
+        assert_eq!(
+            Format::from_accept("text/csv;q=0, text/plain;q=0.1"),
+            Some(Format::Text)
+        );
+        assert_eq!(
+            Format::from_accept("image/png, text/csv;q=0.3"),
+            Some(Format::Csv)
+        );
+        assert_eq!(Format::from_accept("image/png"), None);
+        assert_eq!(Format::from_accept("text/csv;q=0"), None);
+    }
+
+    #[test]
+    fn accept_ignores_invalid_quality_and_case() {
+        assert_eq!(
+            Format::from_accept("TEXT/CSV;q=high, application/json;q=0.5"),
+            Some(Format::Csv)
+        );
+    }
+
+    #[test]
+    fn wildcards_match_json_or_text() {
+        assert_eq!(Format::from_accept("*/*"), Some(Format::Json));
+        assert_eq!(Format::from_accept("text/*"), Some(Format::Text));
+        assert_eq!(Format::from_media_type("application/*"), Some(Format::Json));
+    }
+
+    #[test]
+    fn json_renders_the_whole_response() {
+        let response = PagedResponse::new(1, 10, vec![quote()], false);
+
+        let json = render(&response, Format::Json).unwrap();
+
+        assert_eq!(json, serde_json::to_string(&response).unwrap());
+    }
+
+    #[test]
+    fn text_renders_parts_in_order() {
+        let text = render(&vec![quote(), quote()], Format::Text).unwrap();
+
+        let expected = "Fry: Hi, Bender\nBender: Shut up, baby.\n\nFuturama\nSeason 6\nEpisode 2 - In-A-Gadda-Da-Leela\n";
+        assert_eq!(text, format!("{expected}%\n{expected}"));
+    }
+
+    #[test]
+    fn csv_renders_a_row_for_each_part() {
+        let csv = render(&quote(), Format::Csv).unwrap();
+
+        assert_eq!(
+            csv,
+            "id,show_name,season_no,season_name,episode_no,episode_name,order,character_name,quote_text\n\
+             7,Futurama,6,,2,In-A-Gadda-Da-Leela,1,Fry,\"Hi, Bender\"\n\
+             7,Futurama,6,,2,In-A-Gadda-Da-Leela,2,Bender,\"Shut up, baby.\"\n"
+        );
+    }
+
+    #[test]
+    fn ndjson_renders_a_line_for_each_quote() {
+        let ndjson = render(&vec![quote(), quote()], Format::Ndjson).unwrap();
+
+        let lines = ndjson.lines().collect::<Vec<&str>>();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], serde_json::to_string(&quote()).unwrap());
+    }
+}
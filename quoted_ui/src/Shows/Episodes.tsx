@@ -38,6 +38,7 @@ function Episodes({ getEpisodesEnabled, seasonNo, showName }: EpisodesProps) {
       <Pagination
         currentPage={page}
         hasMore={Boolean(result.data?.hasMore)}
+        totalPages={result.data?.totalPages}
         onNextClicked={nextPage}
         onPageNumberChanged={(page) => setPage(page)}
         onPageSizeChanged={(limit) => setLimit(limit)}
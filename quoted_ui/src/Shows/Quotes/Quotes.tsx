@@ -35,9 +35,7 @@ function Quotes({}: QuotesProps) {
         <ul className={styles["quotes__list"]}>
           {result.data.data.map((d) => (
             <QuoteListItem
-              key={`quote-${d.showName}-${d.seasonNo}-${
-                d.episodeNo
-              }=${JSON.stringify(d.parts)}`}
+              key={`quote-${d.id}`}
               episodeNo={d.episodeNo}
               parts={d.parts}
               seasonNo={d.seasonNo}
@@ -51,6 +49,7 @@ function Quotes({}: QuotesProps) {
       <Pagination
         currentPage={page}
         hasMore={Boolean(result.data?.hasMore)}
+        totalPages={result.data?.totalPages}
         onNextClicked={nextPage}
         onPageNumberChanged={setPage}
         onPageSizeChanged={setLimit}
@@ -29,7 +29,15 @@ export function useGetQuotes(params: UseGetQuotesParams) {
     initialLimit: Pagination.defaultValues.pageSize,
     optionsBuilder: (page, limit) => ({
       queryKey: queryKey(params, page, limit),
-      queryFn: () => getQuotes({ limit, page, showName, seasonNo, episodeNo }),
+      queryFn: () =>
+        getQuotes({
+          limit,
+          page,
+          includeTotal: true,
+          showName,
+          seasonNo,
+          episodeNo,
+        }),
     }),
   });
 }
@@ -40,6 +40,7 @@ function Seasons({ showName, getSeasonsEnabled }: SeasonsProps) {
       <Pagination
         currentPage={page}
         hasMore={Boolean(result.data?.hasMore)}
+        totalPages={result.data?.totalPages}
         onNextClicked={nextPage}
         onPageNumberChanged={(page) => setPage(page)}
         onPageSizeChanged={(limit) => setLimit(limit)}
@@ -73,6 +73,7 @@ function Shows({}: ShowsProps) {
         currentPage={page}
         pageSize={limit}
         hasMore={Boolean(result.data?.hasMore)}
+        totalPages={result.data?.totalPages}
         onNextClicked={nextPage}
         onPreviousClicked={previousPage}
         onPageNumberChanged={(pageNumber) => setPage(pageNumber)}
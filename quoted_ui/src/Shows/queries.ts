@@ -18,7 +18,13 @@ export function useGetShows() {
     ...defaultInitialPageParams,
     optionsBuilder: (page, limit) => ({
       queryKey: ["shows", page, limit, searchTerm],
-      queryFn: () => getShows({ limit, page, name: searchTerm ?? undefined }),
+      queryFn: () =>
+        getShows({
+          limit,
+          page,
+          includeTotal: true,
+          name: searchTerm ?? undefined,
+        }),
     }),
   });
 
@@ -35,7 +41,8 @@ export function useGetSeasons({ showName, enabled }: UseGetSeasonsParams) {
     ...defaultInitialPageParams,
     optionsBuilder: (page, limit) => ({
       queryKey: ["show", showName, "seasons", page, limit],
-      queryFn: () => getSeasons({ limit, page, show: showName }),
+      queryFn: () =>
+        getSeasons({ limit, page, includeTotal: true, show: showName }),
       enabled,
     }),
   });
@@ -54,7 +61,13 @@ export function useGetEpisodes({
     optionsBuilder: (page, limit) => ({
       queryKey: ["show", showName, "season", seasonNo, "episodes", page, limit],
       queryFn: () =>
-        getEpisodes({ limit, page, season: seasonNo, show: showName }),
+        getEpisodes({
+          limit,
+          page,
+          includeTotal: true,
+          season: seasonNo,
+          show: showName,
+        }),
       enabled,
     }),
   });
@@ -14,6 +14,7 @@ interface PaginationProps {
   currentPage: number;
   pageSize?: number;
   hasMore: boolean;
+  totalPages?: number;
   maxPageNumbersDisplayed?: number;
   validPageSizes?: ReadonlyArray<number>;
   onPreviousClicked(): void;
@@ -26,8 +27,33 @@ function getPageNumbersInScope(
   currentPage: number,
   maxPageNumbersDisplayed: number,
   hasMore: boolean,
-  maxPageVisited: number
+  maxPageVisited: number,
+  totalPages: number | undefined
 ) {
+  // when the total is known, show the pages around the current one,
+  // along with the last page so it can be jumped to
+  if (totalPages !== undefined) {
+    const lastInScope = Math.min(
+      totalPages,
+      Math.max(
+        currentPage + Math.floor(maxPageNumbersDisplayed / 2),
+        maxPageNumbersDisplayed + 1
+      )
+    );
+    const pageNumbers = [];
+    for (
+      let pageNumber = Math.max(1, lastInScope - maxPageNumbersDisplayed);
+      pageNumber <= lastInScope;
+      pageNumber++
+    ) {
+      pageNumbers.push(pageNumber);
+    }
+    if (lastInScope < totalPages) {
+      pageNumbers.push(totalPages);
+    }
+    return pageNumbers;
+  }
+
   const maxPageNumber = Math.max(currentPage + Number(hasMore), maxPageVisited);
   const pageNumbers = [];
   for (
@@ -45,6 +71,7 @@ function getPageNumbersInScope(
 function Pagination({
   currentPage,
   hasMore,
+  totalPages,
   pageSize = defaultValues.pageSize,
   validPageSizes = defaultValues.validPageSizes,
   maxPageNumbersDisplayed = defaultValues.maxPageNumbersDisplayed,
@@ -68,7 +95,8 @@ function Pagination({
     currentPage,
     maxPageNumbersDisplayed,
     hasMore,
-    maxPageVisited.current
+    maxPageVisited.current,
+    totalPages
   );
 
   function handlePageSizeChanged(pageSize: number) {
@@ -81,6 +109,7 @@ function Pagination({
       <PageNumbers
         currentPage={currentPage}
         hasMore={hasMore}
+        totalPages={totalPages}
         onNextClicked={onNextClicked}
         onPageNumberClicked={onPageNumberClicked}
         onPreviousClicked={onPreviousClicked}
@@ -104,10 +133,12 @@ interface PageNumbersProps {
   onPageNumberClicked(pageNumber: number): void;
   onNextClicked(): void;
   hasMore: boolean;
+  totalPages?: number;
 }
 function PageNumbers({
   currentPage,
   hasMore,
+  totalPages,
   onNextClicked,
   onPageNumberClicked,
   onPreviousClicked,
@@ -115,7 +146,11 @@ function PageNumbers({
 }: PageNumbersProps) {
   return (
     <div className={styles["page-numbers"]}>
-      <span>Page number</span>
+      <span>
+        {totalPages === undefined
+          ? "Page number"
+          : `Page ${currentPage} of ${totalPages}`}
+      </span>
       <ul className={styles["page-number-buttons"]}>
         <li key={`page-prev`}>
           <Button
@@ -2,18 +2,46 @@
 export interface PagedRequest {
   page: number;
   limit: number;
+  cursor?: string;
+  includeTotal?: boolean;
 }
 
 export interface PagedResponse<T> {
   page: number;
   limit: number;
   hasMore: number;
+  nextCursor: string | undefined;
+  total?: number;
+  totalPages?: number;
   data: Array<T>;
 }
 
+export type SortDirection = "asc" | "desc";
+
+export type ErrorCode =
+  | "bad_request"
+  | "invalid_parameters"
+  | "invalid_body"
+  | "validation_failed"
+  | "unauthorized"
+  | "forbidden"
+  | "not_found"
+  | "method_not_allowed"
+  | "conflict"
+  | "rate_limited"
+  | "database_unavailable"
+  | "database_error"
+  | "internal_error";
+
+export interface FieldError {
+  field: string;
+  message: string;
+}
+
 export interface ErrorDetail {
+  code: ErrorCode;
   message: string | undefined;
-  key: string | undefined;
+  errors: FieldError[];
 }
 
 export type ApiResponse<T> =
@@ -53,6 +81,24 @@ interface GetSeasonsInShowResponseItem {
 }
 //#endregion
 
+//#region ========= Character Models =========
+export type GetCharactersInShowRequest = PagedRequest &
+  GetCharactersInShowRequestParams;
+
+export type GetCharactersInShowResponse =
+  PagedResponse<GetCharactersInShowResponseItem>;
+
+interface GetCharactersInShowRequestParams {
+  show: string;
+  name?: string;
+}
+
+interface GetCharactersInShowResponseItem {
+  name: string;
+  quoteCount: number;
+}
+//#endregion
+
 //#region ========= Episode Models =========
 export type GetEpisodesInSeasonRequest = PagedRequest &
   GetEpisodesInSeasonRequestParams;
@@ -79,8 +125,12 @@ interface GetRandomQuoteRequestParams {
   episodeNo: number;
   characterName: string;
 }
+interface GetRandomQuoteRequestOptions {
+  count?: number;
+  seed?: number;
+}
 // prettier-ignore
-export type GetRandomQuoteRequest =
+export type GetRandomQuoteRequest = GetRandomQuoteRequestOptions & (
   | Pick<GetRandomQuoteRequestParams, 'showName'>
   | Pick<GetRandomQuoteRequestParams, 'showName'|'seasonNo'>
   | Pick<GetRandomQuoteRequestParams, 'showName'|'seasonNo'|'episodeNo'>
@@ -88,21 +138,45 @@ export type GetRandomQuoteRequest =
   | Pick<GetRandomQuoteRequestParams, 'characterName'|'showName'>
   | Pick<GetRandomQuoteRequestParams, 'characterName'|'showName'|'seasonNo'>
   | Pick<GetRandomQuoteRequestParams, 'characterName'|'showName'|'seasonNo'|'episodeNo'>
-  | GetRandomQuoteRequestParams;
+  | GetRandomQuoteRequestParams
+);
 
 export type GetQuotesRequest = PagedRequest & GetQuotesRequestParams;
 
 export type GetQuotesResponse = PagedResponse<GetQuotesResponseItem>;
 
-export type GetRandomQuoteResponse = GetRandomQuoteResponseItem;
+export type GetRandomQuoteResponse = Array<GetRandomQuoteResponseItem>;
+
+export type GetDailyQuoteRequest = Partial<GetDailyQuoteRequestParams>;
+
+export type GetDailyQuoteResponse = GetQuotesResponseItem;
+
+interface GetDailyQuoteRequestParams {
+  showName: string;
+  characterName: string;
+  timezone: string;
+}
+
+export type GetQuoteRequest = GetQuoteRequestRouteParams;
+
+export type GetQuoteResponse = GetQuotesResponseItem;
+
+interface GetQuoteRequestRouteParams {
+  id: number;
+}
 
 interface GetQuotesRequestParams {
   showName?: string;
   seasonNo?: number;
   episodeNo?: number;
+  sort?: QuoteSort;
+  direction?: SortDirection;
+  seed?: number;
 }
+export type QuoteSort = "chronological" | "character" | "length" | "random";
 
 interface GetQuotesResponseItem {
+  id: number;
   showName: string;
   seasonNo: number;
   seasonName?: string;
@@ -112,6 +186,7 @@ interface GetQuotesResponseItem {
 }
 
 interface GetRandomQuoteResponseItem {
+  id: number;
   showName: string;
   seasonNo: number;
   seasonName: string | undefined;
@@ -120,9 +195,62 @@ interface GetRandomQuoteResponseItem {
   parts: Array<QuotePart>;
 }
 
+export type SearchQuotesRequest = PagedRequest & SearchQuotesRequestParams;
+
+export type SearchQuotesResponse = PagedResponse<SearchQuotesResponseItem>;
+
+interface SearchQuotesRequestParams
+  extends Omit<GetQuotesRequestParams, "sort" | "direction" | "seed"> {
+  q: string;
+}
+
+interface SearchQuotesResponseItem extends GetQuotesResponseItem {
+  rank: number;
+  snippet: string;
+}
+
 export interface QuotePart {
   characterName: string;
   order: number;
   quoteText: string;
 }
 //#endregion
+
+//#region ========= Stats Models =========
+export type GetStatsRequest = Partial<GetStatsRequestParams>;
+
+interface GetStatsRequestParams {
+  showName: string;
+  top: number;
+}
+
+export interface GetStatsResponse {
+  totals: StatsTotals;
+  averagePartsPerQuote: number;
+  topCharacters: Array<CharacterStats>;
+  topEpisodes: Array<EpisodeStats>;
+}
+
+interface StatsTotals {
+  shows: number;
+  seasons: number;
+  episodes: number;
+  characters: number;
+  quotes: number;
+  quoteParts: number;
+}
+
+interface CharacterStats {
+  showName: string;
+  name: string;
+  quoteCount: number;
+}
+
+interface EpisodeStats {
+  showName: string;
+  seasonNo: number;
+  episodeNo: number;
+  episodeName: string | undefined;
+  quoteCount: number;
+}
+//#endregion
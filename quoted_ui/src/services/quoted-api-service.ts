@@ -1,6 +1,12 @@
 import {
+  GetCharactersInShowRequest,
+  GetCharactersInShowResponse,
+  GetDailyQuoteRequest,
+  GetDailyQuoteResponse,
   GetEpisodesInSeasonRequest,
   GetEpisodesInSeasonResponse,
+  GetQuoteRequest,
+  GetQuoteResponse,
   GetQuotesRequest,
   GetQuotesResponse,
   GetRandomQuoteRequest,
@@ -9,6 +15,10 @@ import {
   GetSeasonsInShowResponse,
   GetShowsRequest,
   GetShowsResponse,
+  GetStatsRequest,
+  GetStatsResponse,
+  SearchQuotesRequest,
+  SearchQuotesResponse,
 } from "./quoted-api-models";
 
 import { proxy } from "./quoted-api-proxy";
@@ -17,9 +27,14 @@ import { proxy } from "./quoted-api-proxy";
 const endpoint = {
   shows: () => "/shows",
   randomQuote: () => "/quote/random",
+  dailyQuote: () => "/quote/daily",
+  quote: (id: number) => `/quote/${id}`,
   seasons: (showName: string) => `/show/${showName}/seasons`,
+  characters: (showName: string) => `/show/${showName}/characters`,
   episodes: (showName: string, seasonNo: number) => `/show/${showName}/season/${seasonNo}/episodes`,
   quotes: () => `/quotes`,
+  searchQuotes: () => `/quotes/search`,
+  stats: () => `/stats`,
 } as const;
 
 export async function getShows(
@@ -44,6 +59,16 @@ export async function getSeasons(
   return result.data;
 }
 
+export async function getCharacters(
+  request: GetCharactersInShowRequest
+): Promise<GetCharactersInShowResponse> {
+  const { show, ...query } = request;
+  const result = await proxy.get(endpoint.characters(show), {
+    params: query,
+  });
+  return result.data;
+}
+
 export async function getEpisodes(
   request: GetEpisodesInSeasonRequest
 ): Promise<GetEpisodesInSeasonResponse> {
@@ -71,3 +96,37 @@ export async function getRandomQuote(
   });
   return result.data;
 }
+
+export async function getDailyQuote(
+  request: GetDailyQuoteRequest
+): Promise<GetDailyQuoteResponse> {
+  const result = await proxy.get(endpoint.dailyQuote(), {
+    params: request,
+  });
+  return result.data;
+}
+
+export async function getQuote(
+  request: GetQuoteRequest
+): Promise<GetQuoteResponse> {
+  const result = await proxy.get(endpoint.quote(request.id));
+  return result.data;
+}
+
+export async function searchQuotes(
+  request: SearchQuotesRequest
+): Promise<SearchQuotesResponse> {
+  const result = await proxy.get(endpoint.searchQuotes(), {
+    params: request,
+  });
+  return result.data;
+}
+
+export async function getStats(
+  request: GetStatsRequest
+): Promise<GetStatsResponse> {
+  const result = await proxy.get(endpoint.stats(), {
+    params: request,
+  });
+  return result.data;
+}